@@ -0,0 +1,178 @@
+use std::{collections::HashMap, mem};
+
+use crate::{evaluate_guess, game::check_guess, CellValue, Evaluation, GameState, GuessError};
+
+/// An adversarial game with no fixed answer. Every guess is scored against all the
+/// words that are still possible, and the game keeps whichever group of words
+/// shares the most common feedback, so the player has to corner it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AbsurdleGame {
+    candidates: Vec<String>,
+    guesses: Vec<Evaluation>,
+    current_guess: String,
+    word_length: usize,
+    state: GameState,
+}
+
+impl AbsurdleGame {
+    pub fn new<I, S>(candidates: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let candidates = candidates
+            .into_iter()
+            .map(|w| w.into().to_lowercase())
+            .collect::<Vec<_>>();
+        Self {
+            word_length: candidates.first().map_or(0, |w| w.chars().count()),
+            candidates,
+            guesses: Vec::new(),
+            current_guess: String::new(),
+            state: GameState::InProgress,
+        }
+    }
+
+    /// The words that are still consistent with every guess so far.
+    pub fn candidates(&self) -> &[String] {
+        &self.candidates
+    }
+
+    pub fn guesses(&self) -> &[Evaluation] {
+        &self.guesses
+    }
+
+    pub fn current_guess(&self) -> &str {
+        &self.current_guess
+    }
+
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state != GameState::InProgress
+    }
+
+    pub fn type_letter(&mut self, c: char) -> bool {
+        if self.is_over() || self.current_guess.chars().count() >= self.word_length {
+            return false;
+        }
+        self.current_guess.push(c.to_ascii_lowercase());
+        true
+    }
+
+    pub fn backspace(&mut self) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        self.current_guess.pop().is_some()
+    }
+
+    /// Scores the current guess against the remaining candidates and narrows them
+    /// down to the largest group.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GuessError`] and leaves the current guess untouched if the game
+    /// is already over or the guess isn't a playable word.
+    pub fn submit(&mut self) -> Result<Evaluation, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+        check_guess(&self.current_guess, self.word_length)?;
+        let guess = mem::take(&mut self.current_guess);
+        Ok(self.add_guess(&guess))
+    }
+
+    /// Plays `guess` without checking it, e.g. when restoring a saved game.
+    pub fn add_guess(&mut self, guess: &str) -> Evaluation {
+        let guess = guess.to_lowercase();
+        let mut groups: Vec<(Evaluation, Vec<String>)> = Vec::new();
+        let mut group_index = HashMap::new();
+        for candidate in mem::take(&mut self.candidates) {
+            let pattern = evaluate_guess(&candidate, &guess);
+            let idx = *group_index.entry(pattern.clone()).or_insert_with(|| {
+                groups.push((pattern, Vec::new()));
+                groups.len() - 1
+            });
+            groups[idx].1.push(candidate);
+        }
+
+        // Largest group wins. Ties go to the group that gives away the least, then
+        // to whichever was seen first so replaying a game gives the same result.
+        let (pattern, candidates) = groups
+            .into_iter()
+            .enumerate()
+            .max_by_key(|(idx, (pattern, words))| {
+                (
+                    words.len(),
+                    std::cmp::Reverse(revealed(pattern)),
+                    std::cmp::Reverse(*idx),
+                )
+            })
+            .map(|(_, group)| group)
+            .unwrap_or_else(|| (guess.chars().map(CellValue::Absent).collect(), Vec::new()));
+
+        self.candidates = candidates;
+        if pattern.iter().all(|c| matches!(c, CellValue::Correct(_))) {
+            self.state = GameState::Won;
+        }
+        self.guesses.push(pattern.clone());
+        pattern
+    }
+}
+
+/// How much a pattern gives away: greens count double.
+fn revealed(pattern: &[CellValue]) -> usize {
+    pattern
+        .iter()
+        .map(|c| match c {
+            CellValue::Correct(_) => 2,
+            CellValue::Present(_) => 1,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{CellValue, GameState};
+
+    use super::AbsurdleGame;
+
+    #[test]
+    fn test_keeps_largest_group() {
+        let mut game = AbsurdleGame::new(["crane", "crate", "grate", "irate", "slate"]);
+        let pattern = game.add_guess("plate");
+        assert_eq!(game.candidates(), ["crate", "grate", "irate"]);
+        assert_eq!(
+            pattern,
+            vec![
+                CellValue::Absent('p'),
+                CellValue::Absent('l'),
+                CellValue::Correct('a'),
+                CellValue::Correct('t'),
+                CellValue::Correct('e'),
+            ]
+        );
+        assert_eq!(game.state(), GameState::InProgress);
+    }
+
+    #[test]
+    fn test_win_when_cornered() {
+        let mut game = AbsurdleGame::new(["crane", "crate"]);
+        // both words give different feedback for "crane", so it keeps the one that
+        // gives away less
+        game.add_guess("crane");
+        assert_eq!(game.candidates(), ["crate"]);
+        assert_eq!(game.state(), GameState::InProgress);
+        game.add_guess("crate");
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.guesses().len(), 2);
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Serialize, Deserialize)]
+pub enum CellValue {
+    Empty,
+    Typing(char),
+    Absent(char),
+    Present(char),
+    Correct(char),
+}
+
+impl CellValue {
+    /// The letter in this cell, if there is one.
+    pub fn letter(self) -> Option<char> {
+        match self {
+            Self::Empty => None,
+            Self::Typing(c) | Self::Absent(c) | Self::Present(c) | Self::Correct(c) => Some(c),
+        }
+    }
+
+    pub fn score_char(self) -> char {
+        match self {
+            Self::Empty | Self::Typing(_) | Self::Absent(_) => '⬜',
+            Self::Present(_) => '🟨',
+            Self::Correct(_) => '🟩',
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use std::collections::HashMap;
+
+use crate::CellValue;
+
+pub fn evaluate_guess(word: &str, guess: &str) -> Vec<CellValue> {
+    let mut vals = Vec::with_capacity(word.len());
+    let mut counts = word
+        .chars()
+        .fold(HashMap::new(), |mut acc: HashMap<char, usize>, c| {
+            *acc.entry(c).or_insert(0) += 1;
+            acc
+        });
+
+    // find correct characters
+    for (w, g) in word.chars().zip(guess.chars()) {
+        let cell = if w == g {
+            if let Some(count) = counts.get_mut(&g) {
+                *count = count.saturating_sub(1);
+            }
+            Some(CellValue::Correct(g))
+        } else {
+            None
+        };
+        vals.push(cell);
+    }
+
+    // categorize the rest of the characters
+    for (idx, g) in guess.chars().enumerate() {
+        let cell = match (vals[idx], counts.get(&g)) {
+            (v @ Some(_), _) => v,
+            (None, Some(f)) if *f > 0 => {
+                if let Some(count) = counts.get_mut(&g) {
+                    *count = count.saturating_sub(1);
+                }
+                Some(CellValue::Present(g))
+            }
+            (_, _) => Some(CellValue::Absent(g)),
+        };
+        vals[idx] = cell;
+    }
+
+    vals.into_iter().map(Option::unwrap).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use crate::CellValue::{Absent, Correct, Present};
+
+    use super::evaluate_guess;
+
+    #[test]
+    fn test_repeated_letters() {
+        assert_eq!(
+            evaluate_guess("abbey", "babes"),
+            vec![
+                Present('b'),
+                Present('a'),
+                Correct('b'),
+                Correct('e'),
+                Absent('s')
+            ]
+        );
+        assert_eq!(
+            evaluate_guess("crane", "eerie"),
+            vec![
+                Absent('e'),
+                Absent('e'),
+                Present('r'),
+                Absent('i'),
+                Correct('e')
+            ]
+        );
+    }
+}
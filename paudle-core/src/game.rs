@@ -0,0 +1,259 @@
+use std::{fmt, mem};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{check_hard_mode, evaluate_guess, is_valid_guess, CellValue, HardModeViolation};
+
+pub type Evaluation = Vec<CellValue>;
+
+pub const MIN_GUESSES: usize = 3;
+pub const DEFAULT_MAX_GUESSES: usize = 6;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub enum GameState {
+    InProgress,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum GuessError {
+    GameOver,
+    Incomplete,
+    NotInWordList,
+    HardMode(HardModeViolation),
+}
+
+impl fmt::Display for GuessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::GameOver => write!(f, "The game is over"),
+            Self::Incomplete => write!(f, "Not enough letters"),
+            Self::NotInWordList => write!(f, "Word not in word list"),
+            Self::HardMode(violation) => violation.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for GuessError {}
+
+/// The checks every game type makes before scoring a guess.
+pub(crate) fn check_guess(guess: &str, word_length: usize) -> Result<(), GuessError> {
+    if guess.chars().count() != word_length {
+        return Err(GuessError::Incomplete);
+    }
+    if !is_valid_guess(guess) {
+        return Err(GuessError::NotInWordList);
+    }
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Game {
+    word: String,
+    guesses: Vec<Evaluation>,
+    current_guess: String,
+    word_length: usize,
+    max_guesses: Option<usize>,
+    state: GameState,
+    hard_mode: bool,
+}
+
+impl Game {
+    pub fn new(word: impl Into<String>) -> Self {
+        let word = word.into().to_lowercase();
+        Self {
+            word_length: word.chars().count(),
+            word,
+            guesses: Vec::new(),
+            current_guess: String::new(),
+            max_guesses: Some(DEFAULT_MAX_GUESSES),
+            state: GameState::InProgress,
+            hard_mode: false,
+        }
+    }
+
+    /// Limits the game to `max_guesses` guesses, or lets it go on until the word is
+    /// found if `None`. Limits below [`MIN_GUESSES`] are raised to it.
+    #[must_use]
+    pub fn with_max_guesses(mut self, max_guesses: Option<usize>) -> Self {
+        self.max_guesses = max_guesses.map(|max| max.max(MIN_GUESSES));
+        self
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    pub fn guesses(&self) -> &[Evaluation] {
+        &self.guesses
+    }
+
+    pub fn current_guess(&self) -> &str {
+        &self.current_guess
+    }
+
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn max_guesses(&self) -> Option<usize> {
+        self.max_guesses
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn hard_mode(&self) -> bool {
+        self.hard_mode
+    }
+
+    /// Hard mode can only be changed before the first guess. Returns `false` if the
+    /// setting couldn't be changed.
+    pub fn set_hard_mode(&mut self, hard_mode: bool) -> bool {
+        if !self.guesses.is_empty() {
+            return false;
+        }
+        self.hard_mode = hard_mode;
+        true
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state != GameState::InProgress
+    }
+
+    /// Adds a letter to the current guess. Returns `false` if nothing changed.
+    pub fn type_letter(&mut self, c: char) -> bool {
+        if self.is_over() || self.current_guess.chars().count() >= self.word_length {
+            return false;
+        }
+        self.current_guess.push(c.to_ascii_lowercase());
+        true
+    }
+
+    /// Removes the last letter of the current guess. Returns `false` if nothing changed.
+    pub fn backspace(&mut self) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        self.current_guess.pop().is_some()
+    }
+
+    /// Checks the current guess against the rules and, if it's allowed, scores it
+    /// against the word and records it.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GuessError`] and leaves the current guess untouched if the game
+    /// is already over or the guess isn't a playable word.
+    pub fn submit(&mut self) -> Result<Evaluation, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+        check_guess(&self.current_guess, self.word_length)?;
+        if self.hard_mode {
+            check_hard_mode(&self.guesses, &self.current_guess).map_err(GuessError::HardMode)?;
+        }
+        let guess = mem::take(&mut self.current_guess);
+        let evaluation = evaluate_guess(&self.word, &guess);
+        self.add_guess(evaluation.clone());
+        Ok(evaluation)
+    }
+
+    /// Records an already scored guess, e.g. when restoring a saved game.
+    pub fn add_guess(&mut self, evaluation: Evaluation) {
+        let correct = evaluation
+            .iter()
+            .all(|g| matches!(g, CellValue::Correct(_)));
+        self.guesses.push(evaluation);
+        if correct {
+            self.state = GameState::Won;
+        } else if Some(self.guesses.len()) == self.max_guesses {
+            self.state = GameState::Lost;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::HardModeViolation;
+
+    use super::{Game, GameState, GuessError};
+
+    fn play(game: &mut Game, guess: &str) -> Result<(), GuessError> {
+        guess.chars().for_each(|c| {
+            game.type_letter(c);
+        });
+        game.submit().map(|_| ())
+    }
+
+    #[test]
+    fn test_win() {
+        let mut game = Game::new("crane");
+        assert_eq!(play(&mut game, "crate"), Ok(()));
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(play(&mut game, "CRANE"), Ok(()));
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(play(&mut game, "crate"), Err(GuessError::GameOver));
+        assert_eq!(game.guesses().len(), 2);
+    }
+
+    #[test]
+    fn test_lose() {
+        let mut game = Game::new("crane");
+        for _ in 0..6 {
+            assert_eq!(play(&mut game, "crate"), Ok(()));
+        }
+        assert_eq!(game.state(), GameState::Lost);
+    }
+
+    #[test]
+    fn test_guess_limits() {
+        let mut game = Game::new("crane").with_max_guesses(Some(3));
+        for _ in 0..3 {
+            assert_eq!(play(&mut game, "crate"), Ok(()));
+        }
+        assert_eq!(game.state(), GameState::Lost);
+
+        let mut game = Game::new("crane").with_max_guesses(None);
+        for _ in 0..20 {
+            assert_eq!(play(&mut game, "crate"), Ok(()));
+        }
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(play(&mut game, "crane"), Ok(()));
+        assert_eq!(game.state(), GameState::Won);
+
+        let game = Game::new("crane").with_max_guesses(Some(1));
+        assert_eq!(game.max_guesses(), Some(3));
+    }
+
+    #[test]
+    fn test_rejected_guesses() {
+        let mut game = Game::new("crane");
+        assert_eq!(play(&mut game, "cran"), Err(GuessError::Incomplete));
+        assert!(game.backspace());
+        assert_eq!(game.current_guess(), "cra");
+        assert!(game.type_letter('z'));
+        assert!(game.type_letter('z'));
+        assert!(!game.type_letter('z'));
+        assert_eq!(game.submit(), Err(GuessError::NotInWordList));
+        assert_eq!(game.current_guess(), "crazz");
+        assert!(game.guesses().is_empty());
+    }
+
+    #[test]
+    fn test_hard_mode() {
+        let mut game = Game::new("crane");
+        assert!(game.set_hard_mode(true));
+        assert_eq!(play(&mut game, "cable"), Ok(()));
+        assert!(!game.set_hard_mode(false));
+        assert_eq!(
+            play(&mut game, "curve"),
+            Err(GuessError::HardMode(HardModeViolation::MissingPresent('a')))
+        );
+        assert_eq!(game.guesses().len(), 1);
+        game.current_guess.clear();
+        assert_eq!(play(&mut game, "crate"), Ok(()));
+    }
+}
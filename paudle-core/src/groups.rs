@@ -0,0 +1,49 @@
+//! Daily leaderboards shared by a group of friends through `paudle-server`. Each
+//! member posts their result for the day's puzzle, and the server only shows their
+//! board to members who have finished that puzzle too.
+use serde::{Deserialize, Serialize};
+
+use crate::Evaluation;
+
+/// One member's result for one daily puzzle.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DailyResult {
+    pub rows: Vec<Evaluation>,
+    pub solved: bool,
+    /// From the first letter typed to the last guess, when the game knows.
+    pub seconds: Option<u64>,
+}
+
+/// A group's standings for one puzzle, as one member sees them.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    pub name: String,
+    pub puzzle: u32,
+    /// Everyone who has played `puzzle`, best first.
+    pub today: Vec<Entry>,
+    /// The seven puzzles up to and including `puzzle`, best first.
+    pub week: Vec<Summary>,
+    /// The thirty puzzles up to and including `puzzle`, best first.
+    pub month: Vec<Summary>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    pub name: String,
+    /// Guesses it took to solve, or `None` if they didn't.
+    pub guesses: Option<usize>,
+    pub seconds: Option<u64>,
+    /// Their board, once whoever is looking has finished the puzzle themselves.
+    pub board: Option<Vec<Evaluation>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Summary {
+    pub name: String,
+    pub played: usize,
+    pub solved: usize,
+    /// Over the puzzles they solved.
+    pub average_guesses: Option<f64>,
+    /// Over the puzzles they solved with a time.
+    pub average_seconds: Option<f64>,
+}
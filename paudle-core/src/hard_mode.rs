@@ -0,0 +1,134 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{CellValue, Evaluation};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum HardModeViolation {
+    /// A letter already known to be correct was moved or dropped.
+    MissingCorrect { position: usize, letter: char },
+    /// A letter already known to be in the word was left out.
+    MissingPresent(char),
+}
+
+impl fmt::Display for HardModeViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingCorrect { position, letter } => write!(
+                f,
+                "{} letter must be {}",
+                ordinal(position + 1),
+                letter.to_ascii_uppercase()
+            ),
+            Self::MissingPresent(letter) => {
+                write!(f, "Guess must contain {}", letter.to_ascii_uppercase())
+            }
+        }
+    }
+}
+
+fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+/// Checks that `guess` uses every hint revealed by the previous guesses: correct
+/// letters stay in place and present letters are reused at least as many times as
+/// they were revealed.
+///
+/// # Errors
+///
+/// Returns the first constraint the guess breaks.
+pub fn check_hard_mode(guesses: &[Evaluation], guess: &str) -> Result<(), HardModeViolation> {
+    let guess = guess.chars().collect::<Vec<_>>();
+
+    for evaluation in guesses {
+        for (position, cell) in evaluation.iter().enumerate() {
+            if let CellValue::Correct(letter) = cell {
+                if guess.get(position) != Some(letter) {
+                    return Err(HardModeViolation::MissingCorrect {
+                        position,
+                        letter: *letter,
+                    });
+                }
+            }
+        }
+    }
+
+    for evaluation in guesses {
+        let revealed =
+            evaluation
+                .iter()
+                .fold(HashMap::new(), |mut acc: HashMap<char, usize>, cell| {
+                    if let CellValue::Correct(c) | CellValue::Present(c) = cell {
+                        *acc.entry(*c).or_insert(0) += 1;
+                    }
+                    acc
+                });
+        for cell in evaluation {
+            if let CellValue::Present(letter) = cell {
+                let used = guess.iter().filter(|c| *c == letter).count();
+                if used < revealed[letter] {
+                    return Err(HardModeViolation::MissingPresent(*letter));
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use crate::evaluate_guess;
+
+    use super::{check_hard_mode, HardModeViolation};
+
+    #[test]
+    fn test_hard_mode() {
+        let guesses = vec![evaluate_guess("crane", "cable")];
+
+        assert_eq!(check_hard_mode(&guesses, "crane"), Ok(()));
+        assert_eq!(check_hard_mode(&guesses, "caste"), Ok(()));
+        assert_eq!(
+            check_hard_mode(&guesses, "roast"),
+            Err(HardModeViolation::MissingCorrect {
+                position: 0,
+                letter: 'c'
+            })
+        );
+        assert_eq!(
+            check_hard_mode(&guesses, "curve"),
+            Err(HardModeViolation::MissingPresent('a'))
+        );
+    }
+
+    #[test]
+    fn test_repeated_present_letters() {
+        let guesses = vec![evaluate_guess("error", "rural")];
+
+        assert_eq!(check_hard_mode(&guesses, "sorry"), Ok(()));
+        assert_eq!(
+            check_hard_mode(&guesses, "barns"),
+            Err(HardModeViolation::MissingPresent('r'))
+        );
+    }
+
+    #[test]
+    fn test_messages() {
+        let second = HardModeViolation::MissingCorrect {
+            position: 1,
+            letter: 'a',
+        };
+        assert_eq!(second.to_string(), "2nd letter must be A");
+        assert_eq!(
+            HardModeViolation::MissingPresent('e').to_string(),
+            "Guess must contain E"
+        );
+    }
+}
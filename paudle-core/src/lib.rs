@@ -0,0 +1,27 @@
+//! The rules of Paudle, free of any browser or UI dependencies.
+#![allow(clippy::module_name_repetitions)]
+mod absurdle;
+mod cell;
+mod evaluate;
+mod game;
+pub mod groups;
+mod hard_mode;
+mod multi;
+pub mod schedule;
+mod solver;
+pub mod sync;
+pub mod versus;
+mod words;
+
+pub use absurdle::AbsurdleGame;
+pub use cell::CellValue;
+pub use evaluate::evaluate_guess;
+pub use game::{Evaluation, Game, GameState, GuessError, DEFAULT_MAX_GUESSES, MIN_GUESSES};
+pub use hard_mode::{check_hard_mode, HardModeViolation};
+pub use multi::{MultiGame, MULTI_BOARD_COUNTS, MULTI_EXTRA_GUESSES};
+pub use schedule::{daily_word, puzzle_date, puzzle_number, SCHEDULE_VERSION};
+pub use solver::{
+    analyze_game, expected_information, rank_candidates, remaining_answers, solve, GuessAnalysis,
+    RankedWord,
+};
+pub use words::{answer_list, dictionary, is_valid_guess, DEFAULT_WORD_LENGTH, WORD_LENGTHS};
@@ -0,0 +1,183 @@
+use std::mem;
+
+use crate::{evaluate_guess, game::check_guess, CellValue, Evaluation, GameState, GuessError};
+
+/// Extra guesses on top of one per board, e.g. 9 guesses for four boards.
+pub const MULTI_EXTRA_GUESSES: usize = 5;
+pub const MULTI_BOARD_COUNTS: [usize; 3] = [2, 4, 8];
+
+/// One guess played against several hidden words at once (Dordle, Quordle,
+/// Octordle). Boards stop taking guesses once they're solved.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MultiGame {
+    words: Vec<String>,
+    boards: Vec<Vec<Evaluation>>,
+    guesses: Vec<String>,
+    current_guess: String,
+    word_length: usize,
+    max_guesses: usize,
+    state: GameState,
+}
+
+impl MultiGame {
+    pub fn new(words: Vec<String>) -> Self {
+        let words = words
+            .into_iter()
+            .map(|w| w.to_lowercase())
+            .collect::<Vec<_>>();
+        let word_length = words.first().map_or(0, |w| w.chars().count());
+        debug_assert!(words.iter().all(|w| w.chars().count() == word_length));
+        Self {
+            boards: vec![Vec::new(); words.len()],
+            max_guesses: words.len() + MULTI_EXTRA_GUESSES,
+            words,
+            guesses: Vec::new(),
+            current_guess: String::new(),
+            word_length,
+            state: GameState::InProgress,
+        }
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    pub fn board_count(&self) -> usize {
+        self.words.len()
+    }
+
+    /// The evaluations shown on each board. Solved boards stop at the winning row.
+    pub fn boards(&self) -> &[Vec<Evaluation>] {
+        &self.boards
+    }
+
+    pub fn guesses(&self) -> &[String] {
+        &self.guesses
+    }
+
+    pub fn current_guess(&self) -> &str {
+        &self.current_guess
+    }
+
+    pub fn word_length(&self) -> usize {
+        self.word_length
+    }
+
+    pub fn max_guesses(&self) -> usize {
+        self.max_guesses
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.state != GameState::InProgress
+    }
+
+    /// The guess number (starting at 1) that solved `board`, if it's been solved.
+    pub fn solved_in(&self, board: usize) -> Option<usize> {
+        let evaluations = &self.boards[board];
+        evaluations
+            .last()
+            .filter(|e| e.iter().all(|c| matches!(c, CellValue::Correct(_))))
+            .map(|_| evaluations.len())
+    }
+
+    pub fn type_letter(&mut self, c: char) -> bool {
+        if self.is_over() || self.current_guess.chars().count() >= self.word_length {
+            return false;
+        }
+        self.current_guess.push(c.to_ascii_lowercase());
+        true
+    }
+
+    pub fn backspace(&mut self) -> bool {
+        if self.is_over() {
+            return false;
+        }
+        self.current_guess.pop().is_some()
+    }
+
+    /// Plays the current guess on every unsolved board. The result has one entry per
+    /// board, `None` for boards that were already solved.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`GuessError`] and leaves the current guess untouched if the game
+    /// is already over or the guess isn't a playable word.
+    pub fn submit(&mut self) -> Result<Vec<Option<Evaluation>>, GuessError> {
+        if self.is_over() {
+            return Err(GuessError::GameOver);
+        }
+        check_guess(&self.current_guess, self.word_length)?;
+        let guess = mem::take(&mut self.current_guess);
+        Ok(self.add_guess(&guess))
+    }
+
+    /// Plays `guess` without checking it, e.g. when restoring a saved game.
+    pub fn add_guess(&mut self, guess: &str) -> Vec<Option<Evaluation>> {
+        let guess = guess.to_lowercase();
+        let evaluations = (0..self.board_count())
+            .map(|board| {
+                if self.solved_in(board).is_some() {
+                    return None;
+                }
+                let evaluation = evaluate_guess(&self.words[board], &guess);
+                self.boards[board].push(evaluation.clone());
+                Some(evaluation)
+            })
+            .collect();
+        self.guesses.push(guess);
+
+        if (0..self.board_count()).all(|board| self.solved_in(board).is_some()) {
+            self.state = GameState::Won;
+        } else if self.guesses.len() == self.max_guesses {
+            self.state = GameState::Lost;
+        }
+
+        evaluations
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{GameState, GuessError};
+
+    use super::MultiGame;
+
+    fn play(game: &mut MultiGame, guess: &str) -> Result<(), GuessError> {
+        guess.chars().for_each(|c| {
+            game.type_letter(c);
+        });
+        game.submit().map(|_| ())
+    }
+
+    #[test]
+    fn test_multi_win() {
+        let mut game = MultiGame::new(vec!["crane".into(), "slate".into()]);
+        assert_eq!(game.max_guesses(), 7);
+        assert_eq!(play(&mut game, "slate"), Ok(()));
+        assert_eq!(game.solved_in(1), Some(1));
+        assert_eq!(game.solved_in(0), None);
+        assert_eq!(play(&mut game, "crane"), Ok(()));
+        assert_eq!(game.state(), GameState::Won);
+        assert_eq!(game.solved_in(0), Some(2));
+        assert_eq!(game.boards()[0].len(), 2);
+        // the solved board didn't take the second guess
+        assert_eq!(game.boards()[1].len(), 1);
+    }
+
+    #[test]
+    fn test_multi_loss() {
+        let mut game = MultiGame::new(vec!["crane".into(), "slate".into()]);
+        assert_eq!(play(&mut game, "crane"), Ok(()));
+        for _ in 0..5 {
+            assert_eq!(play(&mut game, "crate"), Ok(()));
+        }
+        assert_eq!(game.state(), GameState::InProgress);
+        assert_eq!(play(&mut game, "crate"), Ok(()));
+        assert_eq!(game.state(), GameState::Lost);
+        assert_eq!(play(&mut game, "slate"), Err(GuessError::GameOver));
+    }
+}
@@ -0,0 +1,157 @@
+//! The daily word schedule.
+//!
+//! Puzzle numbers count days from [`schedule_epoch`], starting at #0. Each answer
+//! list is shuffled with a fixed generator (SplitMix64 driving a Fisher-Yates
+//! shuffle) and played in that order, so no word comes up twice until the whole
+//! list has been used. The list is then reshuffled for the next pass.
+//!
+//! Everything here is spelled out rather than borrowed from `rand`, so the schedule
+//! only changes when we change it. Editing an answer list or anything in this
+//! module changes every future word: bump [`SCHEDULE_VERSION`] and the golden
+//! vectors in the tests when that's intended.
+use chrono::NaiveDate;
+
+use crate::answer_list;
+
+pub const SCHEDULE_VERSION: u32 = 1;
+
+const SCHEDULE_SEED: u64 = 0x7061_7564_6c65_0001;
+
+/// The stream seeded games shuffle with, apart from the daily and multi-board
+/// ones.
+const SEEDED_STREAM: u64 = 0x7365_6564;
+
+/// The day of puzzle #0.
+pub fn schedule_epoch() -> NaiveDate {
+    NaiveDate::from_ymd_opt(2022, 1, 1).unwrap()
+}
+
+/// The puzzle number for `date`, or `None` for days before the schedule started.
+pub fn puzzle_number(date: NaiveDate) -> Option<u32> {
+    u32::try_from((date - schedule_epoch()).num_days()).ok()
+}
+
+/// The day puzzle `number` is played.
+pub fn puzzle_date(number: u32) -> NaiveDate {
+    schedule_epoch() + chrono::Duration::days(i64::from(number))
+}
+
+/// The daily answer of `word_length` for puzzle `number`.
+pub fn daily_word(word_length: usize, number: u32) -> Option<&'static str> {
+    scheduled_word(word_length, 0, u64::from(number))
+}
+
+/// The word at `index` in one of the endless, repeat-free sequences of answers.
+/// Each `stream` is shuffled independently, so games that need several words a
+/// day (like multi-board games) can take them from their own stream without
+/// lining up with the classic daily word.
+pub fn scheduled_word(word_length: usize, stream: u64, index: u64) -> Option<&'static str> {
+    let words = answer_list(word_length)?.lines().collect::<Vec<_>>();
+    let len = words.len() as u64;
+    if len == 0 {
+        return None;
+    }
+    let order = shuffled_order(words.len(), word_length, stream, index / len);
+    // The position always fits: it's below `words.len()`
+    #[allow(clippy::cast_possible_truncation)]
+    Some(words[order[(index % len) as usize]])
+}
+
+/// `count` different answers of `word_length` for a seeded game. A seed always
+/// gives the same words, so links to seeded games keep working.
+pub fn seeded_words(word_length: usize, seed: u64, count: usize) -> Option<Vec<&'static str>> {
+    let words = answer_list(word_length)?.lines().collect::<Vec<_>>();
+    if words.len() < count {
+        return None;
+    }
+    let order = shuffled_order(words.len(), word_length, SEEDED_STREAM, seed);
+    Some(order[..count].iter().map(|idx| words[*idx]).collect())
+}
+
+/// A permutation of `0..len` for one pass through a list.
+fn shuffled_order(len: usize, word_length: usize, stream: u64, pass: u64) -> Vec<usize> {
+    let mut state = SCHEDULE_SEED;
+    for part in [word_length as u64, stream, pass] {
+        state = splitmix64(&mut state) ^ part;
+    }
+    let mut order = (0..len).collect::<Vec<_>>();
+    for idx in (1..len).rev() {
+        // Modulo bias is irrelevant here, we just need it to be fixed
+        #[allow(clippy::cast_possible_truncation)]
+        let other = (splitmix64(&mut state) % (idx as u64 + 1)) as usize;
+        order.swap(idx, other);
+    }
+    order
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::NaiveDate;
+
+    use crate::answer_list;
+
+    use super::{
+        daily_word, puzzle_date, puzzle_number, scheduled_word, seeded_words, shuffled_order,
+    };
+
+    #[test]
+    fn test_puzzle_numbers() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        assert_eq!(puzzle_number(date(2021, 12, 31)), None);
+        assert_eq!(puzzle_number(date(2022, 1, 1)), Some(0));
+        assert_eq!(puzzle_number(date(2023, 2, 17)), Some(412));
+        assert_eq!(puzzle_date(412), date(2023, 2, 17));
+    }
+
+    /// If this fails, every daily word from here on has changed. Only update it
+    /// along with `SCHEDULE_VERSION`.
+    #[test]
+    fn test_golden_schedule() {
+        let words = [0, 1, 2, 412, 1000]
+            .into_iter()
+            .map(|n| daily_word(5, n).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(words, GOLDEN_5);
+        assert_eq!(daily_word(4, 412), Some(GOLDEN_4_412));
+        assert_eq!(daily_word(8, 412), Some(GOLDEN_8_412));
+    }
+
+    const GOLDEN_5: [&str; 5] = ["years", "build", "stoic", "acids", "score"];
+    const GOLDEN_4_412: &str = "vape";
+    const GOLDEN_8_412: &str = "finisher";
+
+    /// If this fails, every shared seeded link now opens a different word.
+    #[test]
+    fn test_golden_seeded_words() {
+        assert_eq!(seeded_words(5, 0, 1), Some(vec!["exert"]));
+        assert_eq!(seeded_words(6, 1234, 1), Some(vec!["toting"]));
+        assert_eq!(
+            seeded_words(5, 0xbeef, 4),
+            Some(vec!["eaves", "gushy", "belle", "straw"])
+        );
+        assert_eq!(seeded_words(5, 0, 100_000), None);
+    }
+
+    #[test]
+    fn test_no_repeats_until_exhausted() {
+        let len = answer_list(5).unwrap().lines().count();
+        let mut order = shuffled_order(len, 5, 0, 0);
+        let next_pass = shuffled_order(len, 5, 0, 1);
+        assert_ne!(order, next_pass);
+        // every word comes up exactly once per pass
+        order.sort_unstable();
+        assert_eq!(order, (0..len).collect::<Vec<_>>());
+        assert_eq!(
+            scheduled_word(5, 0, len as u64),
+            answer_list(5).unwrap().lines().nth(next_pass[0])
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::HashMap;
+
+use crate::{CellValue, Evaluation};
+
+/// Most answers each guess is scored against when ranking. Longer lists are
+/// sampled evenly so a hint before the first guess doesn't take seconds.
+const MAX_SCORED_ANSWERS: usize = 500;
+
+/// A word that could still be the answer, along with how much guessing it next is
+/// expected to narrow things down, in bits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RankedWord<'a> {
+    pub word: &'a str,
+    pub entropy: f64,
+}
+
+/// How a single guess did, compared with the best guess the solver could find
+/// from the same position.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GuessAnalysis {
+    pub guess: String,
+    pub candidates_before: usize,
+    pub candidates_after: usize,
+    /// Bits the guess was expected to reveal.
+    pub expected_information: f64,
+    pub best_guess: Option<String>,
+    pub best_information: f64,
+    /// Bits the guess actually revealed.
+    pub actual_information: f64,
+}
+
+impl GuessAnalysis {
+    /// Expected information as a percentage of the best guess's, capped at 100 for
+    /// guesses that beat the solver's pick.
+    pub fn skill(&self) -> f64 {
+        if self.best_information <= 0.0 {
+            100.0
+        } else {
+            (self.expected_information / self.best_information * 100.0).min(100.0)
+        }
+    }
+
+    /// How many more bits the guess revealed than it was expected to. Negative
+    /// means the feedback was less helpful than average.
+    pub fn luck(&self) -> f64 {
+        self.actual_information - self.expected_information
+    }
+}
+
+/// Every word in `answers` that would have given exactly the feedback in `guesses`.
+pub fn remaining_answers<'a, I>(answers: I, guesses: &[Evaluation]) -> Vec<&'a str>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let guesses = guesses
+        .iter()
+        .map(|evaluation| {
+            let word = evaluation
+                .iter()
+                .filter_map(|c| c.letter())
+                .collect::<String>();
+            (word, evaluation_pattern(evaluation))
+        })
+        .collect::<Vec<_>>();
+    answers
+        .into_iter()
+        .filter(|answer| {
+            guesses.iter().all(|(guess, expected)| {
+                answer.len() == guess.len()
+                    && pattern(answer.as_bytes(), guess.as_bytes()) == *expected
+            })
+        })
+        .collect()
+}
+
+/// Ranks `candidates` by the expected information from guessing each one next,
+/// best first. Equally good words keep their order from `candidates`.
+pub fn rank_candidates<'a>(candidates: &[&'a str]) -> Vec<RankedWord<'a>> {
+    let answers = scored_answers(candidates);
+    let mut ranked = candidates
+        .iter()
+        .map(|&word| RankedWord {
+            word,
+            entropy: entropy(word.as_bytes(), &answers),
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.entropy.total_cmp(&a.entropy));
+    ranked
+}
+
+/// The answers still consistent with `guesses`, best next guess first.
+pub fn solve<'a, I>(answers: I, guesses: &[Evaluation]) -> Vec<RankedWord<'a>>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    rank_candidates(&remaining_answers(answers, guesses))
+}
+
+/// Bits `guess` is expected to reveal when the answer is one of `candidates`. The
+/// guess doesn't have to be a candidate itself.
+pub fn expected_information(guess: &str, candidates: &[&str]) -> f64 {
+    entropy(guess.as_bytes(), &scored_answers(candidates))
+}
+
+/// Replays `guesses` against `answers` and scores each one against the solver.
+#[allow(clippy::cast_precision_loss)]
+pub fn analyze_game<'a, I>(answers: I, guesses: &[Evaluation]) -> Vec<GuessAnalysis>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut candidates = answers.into_iter().collect::<Vec<_>>();
+    let mut analysis = Vec::with_capacity(guesses.len());
+    for evaluation in guesses {
+        let guess = evaluation
+            .iter()
+            .filter_map(|c| c.letter())
+            .collect::<String>();
+        let best = rank_candidates(&candidates).into_iter().next();
+        let remaining =
+            remaining_answers(candidates.iter().copied(), std::slice::from_ref(evaluation));
+        // An answer from outside the list leaves nothing behind; count that as
+        // learning everything that was left to learn.
+        let actual_information = (candidates.len() as f64 / remaining.len().max(1) as f64).log2();
+        analysis.push(GuessAnalysis {
+            expected_information: expected_information(&guess, &candidates),
+            guess,
+            candidates_before: candidates.len(),
+            candidates_after: remaining.len(),
+            best_guess: best.as_ref().map(|b| b.word.to_string()),
+            best_information: best.map_or(0.0, |b| b.entropy),
+            actual_information,
+        });
+        candidates = remaining;
+    }
+    analysis
+}
+
+/// The candidates each guess gets scored against. Longer lists are sampled evenly.
+fn scored_answers<'a>(candidates: &[&'a str]) -> Vec<&'a [u8]> {
+    let step = candidates.len().div_ceil(MAX_SCORED_ANSWERS);
+    candidates
+        .iter()
+        .step_by(step.max(1))
+        .map(|w| w.as_bytes())
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn entropy(guess: &[u8], answers: &[&[u8]]) -> f64 {
+    let mut groups: HashMap<u64, usize> = HashMap::new();
+    for answer in answers {
+        *groups.entry(pattern(answer, guess)).or_insert(0) += 1;
+    }
+    if answers.is_empty() {
+        return 0.0;
+    }
+    let total = answers.len() as f64;
+    groups
+        .values()
+        .map(|&count| {
+            let p = count as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// The feedback for `guess` against `answer` packed into one number, a base 3
+/// digit per letter. Matches [`crate::evaluate_guess`] without allocating, since
+/// ranking scores every pair of candidates.
+fn pattern(answer: &[u8], guess: &[u8]) -> u64 {
+    let mut unmatched = [0_u8; 256];
+    let mut correct = 0_u64;
+    for (idx, (a, g)) in answer.iter().zip(guess).enumerate() {
+        if a == g {
+            correct |= 1 << idx;
+        } else {
+            unmatched[usize::from(*a)] += 1;
+        }
+    }
+
+    let mut code = 0;
+    let mut place = 1;
+    for (idx, g) in guess.iter().enumerate() {
+        let digit = if correct & (1 << idx) != 0 {
+            2
+        } else if unmatched[usize::from(*g)] > 0 {
+            unmatched[usize::from(*g)] -= 1;
+            1
+        } else {
+            0
+        };
+        code += digit * place;
+        place *= 3;
+    }
+    code
+}
+
+fn evaluation_pattern(evaluation: &[CellValue]) -> u64 {
+    evaluation.iter().rev().fold(0, |code, cell| {
+        let digit = match cell {
+            CellValue::Correct(_) => 2,
+            CellValue::Present(_) => 1,
+            _ => 0,
+        };
+        code * 3 + digit
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use crate::evaluate_guess;
+
+    use super::{analyze_game, evaluation_pattern, pattern, rank_candidates, remaining_answers};
+
+    #[test]
+    fn test_pattern_matches_evaluation() {
+        for (answer, guess) in [
+            ("abbey", "babes"),
+            ("crane", "eerie"),
+            ("crate", "trace"),
+            ("speed", "erase"),
+        ] {
+            assert_eq!(
+                pattern(answer.as_bytes(), guess.as_bytes()),
+                evaluation_pattern(&evaluate_guess(answer, guess)),
+                "{} / {}",
+                answer,
+                guess
+            );
+        }
+    }
+
+    #[test]
+    fn test_remaining_answers() {
+        let answers = ["crane", "crate", "grate", "irate", "slate"];
+        let guesses = [evaluate_guess("grate", "plate")];
+        assert_eq!(
+            remaining_answers(answers, &guesses),
+            ["crate", "grate", "irate"]
+        );
+        let guesses = [
+            evaluate_guess("grate", "plate"),
+            evaluate_guess("grate", "crate"),
+        ];
+        assert_eq!(remaining_answers(answers, &guesses), ["grate", "irate"]);
+    }
+
+    #[test]
+    fn test_rank_candidates() {
+        // "crate" and "trace" give different feedback for every word, "crane" and
+        // "grace" can't tell the other two apart
+        let ranked = rank_candidates(&["crane", "crate", "trace", "grace"]);
+        let words = ranked.iter().map(|r| r.word).collect::<Vec<_>>();
+        assert_eq!(words, ["crate", "trace", "crane", "grace"]);
+        assert!((ranked[0].entropy - 2.0).abs() < 1e-9);
+        assert!((ranked[2].entropy - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_analyze_game() {
+        let answers = ["crane", "crate", "trace", "grace"];
+        let guesses = [
+            evaluate_guess("trace", "crane"),
+            evaluate_guess("trace", "trace"),
+        ];
+        let analysis = analyze_game(answers, &guesses);
+        assert_eq!(analysis.len(), 2);
+
+        let first = &analysis[0];
+        assert_eq!(first.guess, "crane");
+        assert_eq!((first.candidates_before, first.candidates_after), (4, 2));
+        assert_eq!(first.best_guess.as_deref(), Some("crate"));
+        assert!((first.skill() - 75.0).abs() < 1e-9);
+        assert!((first.luck() - -0.5).abs() < 1e-9);
+
+        let last = &analysis[1];
+        assert_eq!((last.candidates_before, last.candidates_after), (2, 1));
+        assert!((last.skill() - 100.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,86 @@
+//! The rule for syncing daily histories between devices: for each puzzle, the
+//! result written last wins. The game itself is opaque here, so the same rule
+//! covers every kind of history on both the client and the server.
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A game as it's synced, with when it was written.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncedGame<T> {
+    /// Seconds since the epoch. Games that don't know when they were written use 0,
+    /// and lose to any that do.
+    pub updated_at: i64,
+    pub game: T,
+}
+
+/// A daily history by puzzle number.
+pub type SyncedHistory<T> = BTreeMap<u32, SyncedGame<T>>;
+
+/// Every history a device has, by the key it's stored under.
+pub type SyncedHistories<T> = BTreeMap<String, SyncedHistory<T>>;
+
+/// Merges `theirs` into `ours`, puzzle by puzzle. On a tie `ours` is kept, so
+/// syncing the same thing twice changes nothing. Returns whether `ours` changed.
+pub fn merge_history<T>(ours: &mut SyncedHistory<T>, theirs: SyncedHistory<T>) -> bool {
+    let mut changed = false;
+    for (puzzle, game) in theirs {
+        match ours.get(&puzzle) {
+            Some(existing) if existing.updated_at >= game.updated_at => {}
+            _ => {
+                ours.insert(puzzle, game);
+                changed = true;
+            }
+        }
+    }
+    changed
+}
+
+/// [`merge_history`] for every history. Returns whether anything changed.
+pub fn merge_histories<T>(ours: &mut SyncedHistories<T>, theirs: SyncedHistories<T>) -> bool {
+    theirs.into_iter().fold(false, |changed, (key, history)| {
+        merge_history(ours.entry(key).or_default(), history) || changed
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{merge_histories, SyncedGame, SyncedHistories};
+
+    #[test]
+    fn test_last_writer_wins() {
+        let game = |updated_at, game| SyncedGame { updated_at, game };
+        let mut ours = SyncedHistories::new();
+        ours.insert(
+            "daily".into(),
+            [
+                (1, game(100, "ours")),
+                (2, game(200, "ours")),
+                (3, game(0, "ours")),
+            ]
+            .into(),
+        );
+        let mut theirs = SyncedHistories::new();
+        theirs.insert(
+            "daily".into(),
+            [
+                (1, game(150, "theirs")),
+                (2, game(200, "theirs")),
+                (4, game(0, "theirs")),
+            ]
+            .into(),
+        );
+        theirs.insert("multi".into(), [(1, game(10, "theirs"))].into());
+
+        assert!(merge_histories(&mut ours, theirs.clone()));
+        let daily = &ours["daily"];
+        assert_eq!(daily[&1].game, "theirs");
+        // ties keep what was already there
+        assert_eq!(daily[&2].game, "ours");
+        assert_eq!(daily[&3].game, "ours");
+        assert_eq!(daily[&4].game, "theirs");
+        assert_eq!(ours["multi"][&1].game, "theirs");
+
+        assert!(!merge_histories(&mut ours, theirs));
+    }
+}
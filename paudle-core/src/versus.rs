@@ -0,0 +1,72 @@
+//! The messages of a head-to-head game played through `paudle-server`. Only the
+//! server knows the word and it scores every guess, so nobody can claim a result
+//! they didn't get. Everyone sees the others' rows as colours, never letters.
+use serde::{Deserialize, Serialize};
+
+use crate::{CellValue, Evaluation};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClientMessage {
+    /// The first message on a connection. Rejoining under the same name picks up
+    /// the round where it was left; a name already playing is rejected.
+    Join {
+        name: String,
+    },
+    Guess(String),
+    /// Starts a new round once everyone has finished this one.
+    Rematch,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ServerMessage {
+    /// A round has started. `you` is the id of the player receiving it. Someone
+    /// rejoining a round gets a [`ServerMessage::Scored`] for each of their rows
+    /// so far straight after.
+    Round {
+        word_length: usize,
+        max_guesses: usize,
+        you: usize,
+    },
+    /// Where everyone has got to, sent whenever anyone joins, leaves or guesses.
+    Players(Vec<PlayerView>),
+    /// The score for the guess just sent.
+    Scored(Evaluation),
+    /// Why the guess just sent wasn't scored.
+    Rejected(String),
+    /// Everyone has finished. The standings are best first.
+    Finished {
+        word: String,
+        standings: Vec<Standing>,
+    },
+}
+
+/// A player as everyone else sees them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlayerView {
+    pub id: usize,
+    pub name: String,
+    /// Rows with the letters taken out by [`hide_letters`].
+    pub rows: Vec<Evaluation>,
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Standing {
+    pub name: String,
+    /// Guesses it took to solve, or `None` if they didn't.
+    pub guesses: Option<usize>,
+    /// How long they played for, which breaks ties.
+    pub millis: u64,
+}
+
+/// A scored row with only the colours left.
+pub fn hide_letters(row: &[CellValue]) -> Evaluation {
+    row.iter()
+        .map(|cell| match cell {
+            CellValue::Absent(_) => CellValue::Absent(' '),
+            CellValue::Present(_) => CellValue::Present(' '),
+            CellValue::Correct(_) => CellValue::Correct(' '),
+            CellValue::Typing(_) | CellValue::Empty => CellValue::Empty,
+        })
+        .collect()
+}
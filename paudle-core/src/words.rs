@@ -0,0 +1,94 @@
+use std::{cmp::Ordering, ops::RangeInclusive};
+
+pub const WORD_LENGTHS: RangeInclusive<usize> = 4..=8;
+pub const DEFAULT_WORD_LENGTH: usize = 5;
+
+/// The curated list of words of `length` that can be picked as answers, one word
+/// per line, or `None` if no list of that length is bundled.
+pub fn answer_list(length: usize) -> Option<&'static str> {
+    match length {
+        4 => Some(include_str!("words/awords4.txt")),
+        5 => Some(include_str!("words/awords5.txt")),
+        6 => Some(include_str!("words/awords6.txt")),
+        7 => Some(include_str!("words/awords7.txt")),
+        8 => Some(include_str!("words/awords8.txt")),
+        _ => None,
+    }
+}
+
+/// Every word of `length` that's accepted as a guess. This is a superset of the
+/// answer list, sorted, with each word on its own newline-terminated line.
+pub fn dictionary(length: usize) -> Option<&'static str> {
+    match length {
+        4 => Some(include_str!("words/dict4.txt")),
+        5 => Some(include_str!("words/dict5.txt")),
+        6 => Some(include_str!("words/dict6.txt")),
+        7 => Some(include_str!("words/dict7.txt")),
+        8 => Some(include_str!("words/dict8.txt")),
+        _ => None,
+    }
+}
+
+pub fn is_valid_guess(guess: &str) -> bool {
+    let Some(dictionary) = dictionary(guess.len()) else {
+        return false;
+    };
+    // Every line is the same width, so we can binary search the raw bytes instead
+    // of splitting the list up front.
+    let dictionary = dictionary.as_bytes();
+    let stride = guess.len() + 1;
+    let word_at = |idx: usize| &dictionary[idx * stride..idx * stride + guess.len()];
+    let (mut low, mut high) = (0, dictionary.len() / stride);
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match word_at(mid).cmp(guess.as_bytes()) {
+            Ordering::Less => low = mid + 1,
+            Ordering::Greater => high = mid,
+            Ordering::Equal => return true,
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod test {
+    use super::{answer_list, dictionary, is_valid_guess, WORD_LENGTHS};
+
+    #[test]
+    fn test_word_lists() {
+        for length in WORD_LENGTHS {
+            let answers = answer_list(length).unwrap();
+            assert!(answers.lines().count() > 1000);
+            assert!(answers
+                .lines()
+                .all(|w| w.len() == length && w.chars().all(|c| c.is_ascii_lowercase())));
+
+            let dictionary = dictionary(length).unwrap();
+            assert_eq!(dictionary.len() % (length + 1), 0);
+            assert!(dictionary
+                .lines()
+                .all(|w| w.len() == length && w.chars().all(|c| c.is_ascii_lowercase())));
+            assert!(dictionary
+                .lines()
+                .zip(dictionary.lines().skip(1))
+                .all(|(a, b)| a < b));
+            assert!(answers.lines().all(is_valid_guess));
+        }
+        assert!(answer_list(3).is_none());
+        assert!(dictionary(3).is_none());
+    }
+
+    #[test]
+    fn test_is_valid_guess() {
+        assert!(is_valid_guess("crane"));
+        assert!(is_valid_guess("abaci"));
+        assert!(is_valid_guess("zymes"));
+        // only in the dictionary, never an answer
+        assert!(is_valid_guess("acerb"));
+        assert!(!answer_list(5).unwrap().lines().any(|w| w == "acerb"));
+        assert!(!is_valid_guess("crane\n"));
+        assert!(!is_valid_guess("cran"));
+        assert!(!is_valid_guess("zzzzz"));
+        assert!(!is_valid_guess(""));
+    }
+}
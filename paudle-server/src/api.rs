@@ -0,0 +1,550 @@
+//! The REST API.
+//!
+//! - `GET`/`PUT /profiles/{id}`: a profile's display name.
+//! - `GET /profiles/{id}/histories`: every history synced to a profile.
+//! - `POST /profiles/{id}/histories`: merges a device's histories into the
+//!   profile's, creating it if needed, and returns the merged result. Games dated
+//!   more than a day ahead are refused, since they'd win every merge after.
+//! - `GET /profiles/{id}/histories/{key}`: one history.
+//! - `GET`/`PUT /groups/{id}`: a group's display name.
+//! - `POST /groups/{id}/results/{puzzle}/{profile}`: posts a profile's result for a
+//!   daily puzzle, creating the group if needed, and returns the leaderboard as
+//!   they now see it. Results must be finished games scored against the day's
+//!   answer, and only their colours are kept. A profile's first result for a
+//!   puzzle is final; posting another is a conflict.
+//! - `GET /groups/{id}/leaderboards/{puzzle}?profile={profile}`: the leaderboard
+//!   for a daily puzzle. Boards are only included once `profile` has posted their
+//!   own result for it.
+//!
+//! Profile requests, and group requests made as a profile, carry its key as
+//! `Authorization: Bearer {key}`. The first key a profile writes with becomes its
+//! key; after that, requests with any other are refused, and leaderboards read
+//! with any other are read as nobody.
+use std::{
+    io,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    routing::{get, post},
+    Json, Router,
+};
+use paudle_core::{
+    groups::{DailyResult, Leaderboard},
+    sync::{merge_histories, SyncedHistories, SyncedHistory},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_http::cors::CorsLayer;
+
+use crate::{
+    db::{Data, Db, Profile, Snapshot},
+    group::{check_result, Group},
+};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProfileInfo {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GroupInfo {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Viewer {
+    pub profile: Option<String>,
+}
+
+pub fn router(db: Db) -> Router {
+    Router::new()
+        .route("/profiles/{id}", get(get_profile).put(put_profile))
+        .route(
+            "/profiles/{id}/histories",
+            get(get_histories).post(sync_histories),
+        )
+        .route("/profiles/{id}/histories/{key}", get(get_history))
+        .route("/groups/{id}", get(get_group).put(put_group))
+        .route("/groups/{id}/results/{puzzle}/{profile}", post(post_result))
+        .route("/groups/{id}/leaderboards/{puzzle}", get(get_leaderboard))
+        // The game is served from wherever people host it, not from here
+        .layer(CorsLayer::permissive())
+        .with_state(db)
+}
+
+/// Takes a copy of `data` to write out once the lock has been released.
+fn snapshot(db: &Db, data: &Data) -> Result<Option<Snapshot>, StatusCode> {
+    db.snapshot(data).map_err(save_failed)
+}
+
+async fn persist(db: &Db, snapshot: Option<Snapshot>) -> Result<(), StatusCode> {
+    db.persist(snapshot).await.map_err(save_failed)
+}
+
+fn save_failed(e: io::Error) -> StatusCode {
+    eprintln!("Couldn't save: {}", e);
+    StatusCode::INTERNAL_SERVER_ERROR
+}
+
+async fn get_profile(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<ProfileInfo>, StatusCode> {
+    let data = db.lock();
+    check_key(&data, &id, &headers)?;
+    let profile = data.profiles.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(ProfileInfo {
+        name: profile.name.clone(),
+    }))
+}
+
+async fn put_profile(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(info): Json<ProfileInfo>,
+) -> Result<Json<ProfileInfo>, StatusCode> {
+    let snapshot = {
+        let mut data = db.lock();
+        claim_profile(&mut data, &id, &headers)?;
+        data.profiles
+            .entry(id)
+            .or_default()
+            .name
+            .clone_from(&info.name);
+        snapshot(&db, &data)?
+    };
+    persist(&db, snapshot).await?;
+    Ok(Json(info))
+}
+
+async fn get_histories(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Json<SyncedHistories<Value>>, StatusCode> {
+    let data = db.lock();
+    check_key(&data, &id, &headers)?;
+    let profile = data.profiles.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(profile.histories.clone()))
+}
+
+async fn get_history(
+    State(db): State<Db>,
+    Path((id, key)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> Result<Json<SyncedHistory<Value>>, StatusCode> {
+    let data = db.lock();
+    check_key(&data, &id, &headers)?;
+    let history = data
+        .profiles
+        .get(&id)
+        .and_then(|profile| profile.histories.get(&key))
+        .ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(history.clone()))
+}
+
+/// How far ahead of the server's clock a synced game can be dated.
+const MAX_CLOCK_SKEW: i64 = 24 * 60 * 60;
+
+async fn sync_histories(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(theirs): Json<SyncedHistories<Value>>,
+) -> Result<Json<SyncedHistories<Value>>, StatusCode> {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_secs()).unwrap_or(i64::MAX));
+    if theirs
+        .values()
+        .flat_map(|history| history.values())
+        .any(|game| game.updated_at > now.saturating_add(MAX_CLOCK_SKEW))
+    {
+        return Err(StatusCode::UNPROCESSABLE_ENTITY);
+    }
+    let (merged, snapshot) = {
+        let mut data = db.lock();
+        let profile = claim_profile(&mut data, &id, &headers)?;
+        let changed = merge_histories(&mut profile.histories, theirs);
+        let merged = profile.histories.clone();
+        let snapshot = if changed { snapshot(&db, &data)? } else { None };
+        (merged, snapshot)
+    };
+    persist(&db, snapshot).await?;
+    Ok(Json(merged))
+}
+
+/// A profile's display name, or its id if it has never synced.
+fn display_name(data: &Data, profile: &str) -> String {
+    data.profiles
+        .get(profile)
+        .map_or_else(|| profile.to_string(), |p| p.name.clone())
+}
+
+async fn get_group(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+) -> Result<Json<GroupInfo>, StatusCode> {
+    let data = db.lock();
+    let group = data.groups.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+    Ok(Json(GroupInfo {
+        name: group.name.clone(),
+    }))
+}
+
+async fn put_group(
+    State(db): State<Db>,
+    Path(id): Path<String>,
+    Json(info): Json<GroupInfo>,
+) -> Result<Json<GroupInfo>, StatusCode> {
+    let snapshot = {
+        let mut data = db.lock();
+        data.groups
+            .entry(id)
+            .or_default()
+            .name
+            .clone_from(&info.name);
+        snapshot(&db, &data)?
+    };
+    persist(&db, snapshot).await?;
+    Ok(Json(info))
+}
+
+/// The key sent with a request, if any.
+fn bearer(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .filter(|key| !key.is_empty())
+}
+
+/// Checks the request carries `profile`'s key. Profiles that have never been
+/// written to have no key yet, so nobody can read them.
+fn check_key(data: &Data, profile: &str, headers: &HeaderMap) -> Result<(), StatusCode> {
+    let key = bearer(headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let profile = data.profiles.get(profile).ok_or(StatusCode::NOT_FOUND)?;
+    match &profile.secret {
+        Some(secret) if secret == key => Ok(()),
+        _ => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+/// Checks the request carries `profile`'s key, creating the profile or making it
+/// the key if the profile doesn't have one yet.
+fn claim_profile<'a>(
+    data: &'a mut Data,
+    profile: &str,
+    headers: &HeaderMap,
+) -> Result<&'a mut Profile, StatusCode> {
+    let key = bearer(headers).ok_or(StatusCode::UNAUTHORIZED)?;
+    let profile = data
+        .profiles
+        .entry(profile.to_string())
+        .or_insert_with(|| Profile {
+            name: profile.to_string(),
+            ..Profile::default()
+        });
+    match &profile.secret {
+        Some(secret) if secret != key => return Err(StatusCode::FORBIDDEN),
+        Some(_) => {}
+        None => profile.secret = Some(key.to_string()),
+    }
+    Ok(profile)
+}
+
+async fn post_result(
+    State(db): State<Db>,
+    Path((id, puzzle, profile)): Path<(String, u32, String)>,
+    headers: HeaderMap,
+    Json(result): Json<DailyResult>,
+) -> Result<Json<Leaderboard>, StatusCode> {
+    let result = check_result(puzzle, result).map_err(|_| StatusCode::UNPROCESSABLE_ENTITY)?;
+    let (leaderboard, snapshot) = {
+        let mut data = db.lock();
+        claim_profile(&mut data, &profile, &headers)?;
+        let group = data.groups.entry(id.clone()).or_insert_with(|| Group {
+            name: id.clone(),
+            ..Group::default()
+        });
+        if !group.post(puzzle, profile.clone(), result) {
+            return Err(StatusCode::CONFLICT);
+        }
+        let leaderboard =
+            data.groups[&id].leaderboard(puzzle, Some(&profile), |p| display_name(&data, p));
+        (leaderboard, snapshot(&db, &data)?)
+    };
+    persist(&db, snapshot).await?;
+    Ok(Json(leaderboard))
+}
+
+async fn get_leaderboard(
+    State(db): State<Db>,
+    Path((id, puzzle)): Path<(String, u32)>,
+    Query(viewer): Query<Viewer>,
+    headers: HeaderMap,
+) -> Result<Json<Leaderboard>, StatusCode> {
+    let data = db.lock();
+    let group = data.groups.get(&id).ok_or(StatusCode::NOT_FOUND)?;
+    // Anyone can look, but only a profile's own key shows what it's finished
+    let viewer = viewer.profile.filter(|profile| {
+        data.profiles
+            .get(profile)
+            .and_then(|p| p.secret.as_deref())
+            .is_some_and(|secret| bearer(&headers) == Some(secret))
+    });
+    Ok(Json(group.leaderboard(puzzle, viewer.as_deref(), |p| {
+        display_name(&data, p)
+    })))
+}
+
+#[cfg(test)]
+mod test {
+    use axum::{
+        body::{to_bytes, Body},
+        http::{Request, StatusCode},
+        Router,
+    };
+    use paudle_core::{daily_word, evaluate_guess, sync::SyncedHistories};
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    use crate::db::Db;
+
+    use super::router;
+
+    async fn send(
+        app: &Router,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        send_as(app, None, method, uri, body).await
+    }
+
+    /// Sends a request with a profile's key.
+    async fn send_as(
+        app: &Router,
+        key: Option<&str>,
+        method: &str,
+        uri: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json");
+        if let Some(key) = key {
+            request = request.header("authorization", format!("Bearer {}", key));
+        }
+        let request = request
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_sync() {
+        let path = std::env::temp_dir().join(format!("paudle-server-{}.json", std::process::id()));
+        let app = router(Db::open(Some(path.clone())).unwrap());
+
+        let key = Some("team-key");
+
+        let (status, _) = send_as(&app, key, "GET", "/profiles/team", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let phone = json!({ "daily": {
+            "400": { "updated_at": 100, "game": "phone" },
+            "401": { "updated_at": 300, "game": "phone" },
+        }});
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/profiles/team/histories",
+            Some(phone.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, merged) =
+            send_as(&app, key, "POST", "/profiles/team/histories", Some(phone)).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(merged["daily"]["400"]["game"], "phone");
+
+        let laptop = json!({ "daily": {
+            "400": { "updated_at": 200, "game": "laptop" },
+            "401": { "updated_at": 200, "game": "laptop" },
+        }});
+        let (_, merged) =
+            send_as(&app, key, "POST", "/profiles/team/histories", Some(laptop)).await;
+        assert_eq!(merged["daily"]["400"]["game"], "laptop");
+        assert_eq!(merged["daily"]["401"]["game"], "phone");
+
+        // a game from the far future would win every merge
+        let future = json!({ "daily": { "400": { "updated_at": i64::MAX, "game": "forever" } } });
+        let (status, _) =
+            send_as(&app, key, "POST", "/profiles/team/histories", Some(future)).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        // the profile is only readable and writable with its key
+        for (method, uri) in [
+            ("GET", "/profiles/team"),
+            ("GET", "/profiles/team/histories"),
+            ("GET", "/profiles/team/histories/daily"),
+            ("POST", "/profiles/team/histories"),
+        ] {
+            let body = (method == "POST").then(|| json!({}));
+            let (status, _) = send(&app, method, uri, body.clone()).await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+            let (status, _) = send_as(&app, Some("guess"), method, uri, body).await;
+            assert_eq!(status, StatusCode::FORBIDDEN);
+        }
+        let rename = Some(json!({ "name": "Not The Team" }));
+        let (status, _) = send_as(&app, Some("guess"), "PUT", "/profiles/team", rename).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let (status, profile) = send_as(&app, key, "GET", "/profiles/team", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(profile["name"], "team");
+        send_as(
+            &app,
+            key,
+            "PUT",
+            "/profiles/team",
+            Some(json!({ "name": "The Team" })),
+        )
+        .await;
+
+        // everything survives a restart
+        let app = router(Db::open(Some(path.clone())).unwrap());
+        let (_, history) = send_as(&app, key, "GET", "/profiles/team/histories/daily", None).await;
+        assert_eq!(history, merged["daily"]);
+        let (_, histories) = send_as(&app, key, "GET", "/profiles/team/histories", None).await;
+        assert!(serde_json::from_value::<SyncedHistories<Value>>(histories).is_ok());
+        let (_, profile) = send_as(&app, key, "GET", "/profiles/team", None).await;
+        assert_eq!(profile["name"], "The Team");
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_groups() {
+        let app = router(Db::open(None).unwrap());
+        let answer = daily_word(5, 400).unwrap();
+        let row = |guess: &str| json!(evaluate_guess(answer, guess));
+        let solved = json!({ "rows": [row("fjord"), row(answer)], "solved": true, "seconds": 20 });
+        let board_of = |board: &Value| board["today"][0]["board"].clone();
+
+        let (status, _) = send(&app, "GET", "/groups/pals/leaderboards/400", None).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        let post = |profile: &str| format!("/groups/pals/results/400/{}", profile);
+        let (status, _) = send(&app, "POST", &post("ann"), Some(solved.clone())).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        let (status, board) = send_as(
+            &app,
+            Some("ann-key"),
+            "POST",
+            &post("ann"),
+            Some(solved.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(board["name"], "pals");
+        assert_eq!(board["today"][0]["guesses"], 2);
+        // only the colours are kept
+        assert_eq!(board_of(&board)[1][0]["Correct"], " ");
+
+        // ann's key is hers now
+        let (status, _) = send_as(
+            &app,
+            Some("guess"),
+            "POST",
+            &post("ann"),
+            Some(solved.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+        // and her first result is final
+        let (status, _) = send_as(
+            &app,
+            Some("ann-key"),
+            "POST",
+            &post("ann"),
+            Some(solved.clone()),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        // results have to be finished games
+        let unfinished = json!({ "rows": [row("fjord")], "solved": false, "seconds": null });
+        let empty = json!({ "rows": [], "solved": true, "seconds": null });
+        let lying = json!({ "rows": [row("fjord")], "solved": true, "seconds": null });
+        for result in [unfinished, empty, lying] {
+            let (status, _) =
+                send_as(&app, Some("bob-key"), "POST", &post("bob"), Some(result)).await;
+            assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+        }
+
+        // bob hasn't played yet, so ann's result shows but not the board
+        send_as(
+            &app,
+            Some("ann-key"),
+            "PUT",
+            "/profiles/ann",
+            Some(json!({ "name": "Ann" })),
+        )
+        .await;
+        let leaderboard = "/groups/pals/leaderboards/400?profile=";
+        let (_, board) = send_as(
+            &app,
+            Some("bob-key"),
+            "GET",
+            &format!("{}bob", leaderboard),
+            None,
+        )
+        .await;
+        assert_eq!(board["today"][0]["name"], "Ann");
+        assert_eq!(board["today"][0]["seconds"], 20);
+        assert_eq!(board_of(&board), Value::Null);
+        assert_eq!(board["week"][0]["played"], 1);
+        // and naming someone who has finished doesn't help without their key
+        let (_, board) = send(&app, "GET", &format!("{}ann", leaderboard), None).await;
+        assert_eq!(board_of(&board), Value::Null);
+        let (_, board) = send_as(
+            &app,
+            Some("bob-key"),
+            "GET",
+            &format!("{}ann", leaderboard),
+            None,
+        )
+        .await;
+        assert_eq!(board_of(&board), Value::Null);
+
+        send_as(&app, Some("bob-key"), "POST", &post("bob"), Some(solved)).await;
+        let (_, board) = send_as(
+            &app,
+            Some("bob-key"),
+            "GET",
+            &format!("{}bob", leaderboard),
+            None,
+        )
+        .await;
+        assert_eq!(board["today"].as_array().unwrap().len(), 2);
+        assert!(board_of(&board).is_array());
+
+        send(&app, "PUT", "/groups/pals", Some(json!({ "name": "Pals" }))).await;
+        let (_, group) = send(&app, "GET", "/groups/pals", None).await;
+        assert_eq!(group["name"], "Pals");
+    }
+}
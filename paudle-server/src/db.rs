@@ -0,0 +1,110 @@
+//! Profiles and their histories, and groups and their results, kept in memory and written out to a JSON file
+//! after every change when the server is given one.
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use paudle_core::sync::SyncedHistories;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::group::Group;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Data {
+    pub profiles: BTreeMap<String, Profile>,
+    #[serde(default)]
+    pub groups: BTreeMap<String, Group>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// The games are whatever the client stores; only the sync envelope around them
+    /// matters here.
+    #[serde(default)]
+    pub histories: SyncedHistories<Value>,
+    /// The key a profile's devices prove themselves with before posting to groups,
+    /// set by whichever device posts first.
+    #[serde(default)]
+    pub secret: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct Db {
+    data: Arc<Mutex<Data>>,
+    path: Option<PathBuf>,
+    /// How many snapshots have been taken, to number the next one.
+    snapshots: Arc<AtomicU64>,
+    /// The number of the newest snapshot written out, locked while writing.
+    written: Arc<Mutex<u64>>,
+}
+
+/// Everything as it was when [`Db::snapshot`] was called, ready to write out.
+pub struct Snapshot {
+    json: Vec<u8>,
+    number: u64,
+}
+
+impl Db {
+    /// Loads everything from `path` if it exists. Without a path nothing outlives
+    /// the server, which is what tests want.
+    pub fn open(path: Option<PathBuf>) -> io::Result<Self> {
+        let data = match &path {
+            Some(path) if path.exists() => serde_json::from_slice(&fs::read(path)?)?,
+            _ => Data::default(),
+        };
+        Ok(Self {
+            data: Arc::new(Mutex::new(data)),
+            path,
+            snapshots: Arc::default(),
+            written: Arc::default(),
+        })
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, Data> {
+        self.data.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Copies `data` out to be written once it's unlocked. Take it while still
+    /// holding the lock, so snapshots are numbered in the order the changes were
+    /// made. Without a path there's nothing to write.
+    pub fn snapshot(&self, data: &Data) -> io::Result<Option<Snapshot>> {
+        if self.path.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Snapshot {
+            json: serde_json::to_vec(data)?,
+            number: self.snapshots.fetch_add(1, Ordering::SeqCst) + 1,
+        }))
+    }
+
+    /// Writes `snapshot` out on a blocking thread, to a temporary file first so a
+    /// crash can't leave half a file behind. A snapshot older than one already
+    /// written is dropped, so a slow write can't undo a later change.
+    pub async fn persist(&self, snapshot: Option<Snapshot>) -> io::Result<()> {
+        let (Some(path), Some(snapshot)) = (self.path.clone(), snapshot) else {
+            return Ok(());
+        };
+        let written = Arc::clone(&self.written);
+        tokio::task::spawn_blocking(move || {
+            let mut written = written.lock().unwrap_or_else(|e| e.into_inner());
+            if snapshot.number < *written {
+                return Ok(());
+            }
+            let tmp = path.with_extension("tmp");
+            fs::write(&tmp, snapshot.json)?;
+            fs::rename(tmp, &path)?;
+            *written = snapshot.number;
+            Ok(())
+        })
+        .await
+        .map_err(io::Error::other)?
+    }
+}
@@ -0,0 +1,278 @@
+//! A friend group's daily results, and the leaderboards built from them.
+use std::collections::{btree_map, BTreeMap};
+
+use paudle_core::{
+    daily_word, evaluate_guess,
+    groups::{DailyResult, Entry, Leaderboard, Summary},
+    versus::hide_letters,
+    CellValue, DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH,
+};
+use serde::{Deserialize, Serialize};
+
+const WEEK: u32 = 7;
+const MONTH: u32 = 30;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Group {
+    pub name: String,
+    /// By puzzle number, then by the profile that posted it.
+    #[serde(default)]
+    pub results: BTreeMap<u32, BTreeMap<String, DailyResult>>,
+}
+
+/// Checks `result` is a finished game of the five letter, six guess puzzle `puzzle`,
+/// with every row scored against its answer, and takes the letters out of it. Only
+/// the colours are kept, so no board posted here can give the answer away.
+pub fn check_result(puzzle: u32, result: DailyResult) -> Result<DailyResult, &'static str> {
+    let Some(last) = result.rows.last() else {
+        return Err("no guesses");
+    };
+    if result.rows.len() > DEFAULT_MAX_GUESSES {
+        return Err("too many guesses");
+    }
+    let scored = |row: &Vec<CellValue>| {
+        row.len() == DEFAULT_WORD_LENGTH
+            && row
+                .iter()
+                .all(|c| !matches!(c, CellValue::Empty | CellValue::Typing(_)))
+    };
+    if !result.rows.iter().all(scored) {
+        return Err("unscored guesses");
+    }
+    let answer = daily_word(DEFAULT_WORD_LENGTH, puzzle).ok_or("no such puzzle")?;
+    let honest = |row: &Vec<CellValue>| {
+        let guess = row.iter().filter_map(|c| c.letter()).collect::<String>();
+        evaluate_guess(answer, &guess) == *row
+    };
+    if !result.rows.iter().all(honest) {
+        return Err("guesses scored wrong");
+    }
+    let solved = last.iter().all(|c| matches!(c, CellValue::Correct(_)));
+    if solved != result.solved {
+        return Err("the last guess doesn't match the result");
+    }
+    if !solved && result.rows.len() < DEFAULT_MAX_GUESSES {
+        return Err("unfinished");
+    }
+    Ok(DailyResult {
+        rows: result.rows.iter().map(|row| hide_letters(row)).collect(),
+        ..result
+    })
+}
+
+impl Group {
+    /// Records `profile`'s result for `puzzle`. The first result posted is final,
+    /// so nobody can post a throwaway one to see the boards and then play for
+    /// real. Returns whether it was recorded.
+    pub fn post(&mut self, puzzle: u32, profile: String, result: DailyResult) -> bool {
+        match self.results.entry(puzzle).or_default().entry(profile) {
+            btree_map::Entry::Occupied(_) => false,
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(result);
+                true
+            }
+        }
+    }
+
+    /// The leaderboard as `viewer` should see it: boards for `puzzle` stay hidden
+    /// until they've posted their own. `name` gives a profile's display name.
+    pub fn leaderboard(
+        &self,
+        puzzle: u32,
+        viewer: Option<&str>,
+        name: impl Fn(&str) -> String,
+    ) -> Leaderboard {
+        let day = self.results.get(&puzzle);
+        let finished = viewer.is_some_and(|viewer| day.is_some_and(|d| d.contains_key(viewer)));
+        let mut today = day
+            .into_iter()
+            .flatten()
+            .map(|(profile, result)| Entry {
+                name: name(profile),
+                guesses: result.solved.then_some(result.rows.len()),
+                seconds: result.seconds,
+                board: finished.then(|| result.rows.clone()),
+            })
+            .collect::<Vec<_>>();
+        today.sort_by_key(|e| {
+            (
+                e.guesses.is_none(),
+                e.guesses,
+                e.seconds.unwrap_or(u64::MAX),
+            )
+        });
+        Leaderboard {
+            name: self.name.clone(),
+            puzzle,
+            today,
+            week: self.summaries(puzzle, WEEK, &name),
+            month: self.summaries(puzzle, MONTH, &name),
+        }
+    }
+
+    /// Everyone's results over the `days` puzzles ending with `puzzle`. Most solved
+    /// first, then fewest guesses, then quickest.
+    fn summaries(&self, puzzle: u32, days: u32, name: impl Fn(&str) -> String) -> Vec<Summary> {
+        let first = puzzle.saturating_sub(days - 1);
+        let mut by_profile: BTreeMap<&str, Vec<&DailyResult>> = BTreeMap::new();
+        for day in self.results.range(first..=puzzle).map(|(_, day)| day) {
+            for (profile, result) in day {
+                by_profile.entry(profile).or_default().push(result);
+            }
+        }
+        let mut summaries = by_profile
+            .into_iter()
+            .map(|(profile, results)| {
+                let solved = results.iter().filter(|r| r.solved).collect::<Vec<_>>();
+                let times = solved.iter().filter_map(|r| r.seconds).collect::<Vec<_>>();
+                Summary {
+                    name: name(profile),
+                    played: results.len(),
+                    solved: solved.len(),
+                    average_guesses: average(solved.iter().map(|r| r.rows.len() as f64)),
+                    average_seconds: average(times.into_iter().map(|s| s as f64)),
+                }
+            })
+            .collect::<Vec<_>>();
+        summaries.sort_by(|a, b| {
+            b.solved
+                .cmp(&a.solved)
+                .then(compare_averages(a.average_guesses, b.average_guesses))
+                .then(compare_averages(a.average_seconds, b.average_seconds))
+        });
+        summaries
+    }
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0., 0), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / f64::from(count))
+}
+
+/// Lower is better, and having no average at all is worst.
+fn compare_averages(a: Option<f64>, b: Option<f64>) -> std::cmp::Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use paudle_core::{daily_word, evaluate_guess, groups::DailyResult, CellValue};
+
+    use super::{check_result, Group};
+
+    fn result(guesses: &[&str], seconds: Option<u64>) -> DailyResult {
+        scored("crane", guesses, seconds)
+    }
+
+    fn scored(answer: &str, guesses: &[&str], seconds: Option<u64>) -> DailyResult {
+        let rows = guesses
+            .iter()
+            .map(|guess| evaluate_guess(answer, guess))
+            .collect::<Vec<_>>();
+        DailyResult {
+            solved: guesses.last() == Some(&answer),
+            rows,
+            seconds,
+        }
+    }
+
+    #[test]
+    fn test_leaderboard() {
+        let mut group = Group {
+            name: "Friends".into(),
+            ..Group::default()
+        };
+        group.post(100, "ann".into(), result(&["slate", "crane"], Some(90)));
+        group.post(100, "bob".into(), result(&["moist", "crane"], Some(60)));
+        group.post(100, "cat".into(), result(&["slate"; 6], None));
+        group.post(90, "cat".into(), result(&["crane"], Some(5)));
+        group.post(70, "cat".into(), result(&["crane"], Some(5)));
+        group.post(99, "ann".into(), result(&["crane"], Some(30)));
+        let name = |profile: &str| profile.to_uppercase();
+
+        let board = group.leaderboard(100, Some("ann"), name);
+        let names = board
+            .today
+            .iter()
+            .map(|e| e.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["BOB", "ANN", "CAT"]);
+        assert_eq!(board.today[2].guesses, None);
+        assert!(board.today.iter().all(|e| e.board.is_some()));
+
+        // nobody's board shows until you've finished too
+        let board = group.leaderboard(100, Some("dan"), name);
+        assert!(board.today.iter().all(|e| e.board.is_none()));
+        assert_eq!(board.today[0].guesses, Some(2));
+        assert!(group
+            .leaderboard(100, None, name)
+            .today
+            .iter()
+            .all(|e| e.board.is_none()));
+
+        let names = board
+            .week
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ANN", "BOB", "CAT"]);
+        assert_eq!(board.week[0].average_guesses, Some(1.5));
+        assert_eq!(board.week[0].average_seconds, Some(60.));
+        assert_eq!(board.week[2].played, 1);
+        assert_eq!(board.week[2].average_guesses, None);
+
+        // cat's earlier wins count for the month, but not from before it
+        let names = board
+            .month
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["ANN", "CAT", "BOB"]);
+        assert_eq!(board.month[1].played, 2);
+    }
+
+    #[test]
+    fn test_check_result() {
+        let answer = daily_word(5, 100).unwrap();
+        let result = |guesses: &[&str], seconds| scored(answer, guesses, seconds);
+        let solved = check_result(100, result(&["slate", answer], Some(9))).unwrap();
+        assert_eq!(solved.rows[1], vec![CellValue::Correct(' '); 5]);
+        assert_eq!(solved.seconds, Some(9));
+        assert!(check_result(100, result(&["fjord"; 6], None)).is_ok());
+
+        assert!(check_result(100, result(&[], None)).is_err());
+        assert!(check_result(100, result(&["fjord"; 7], None)).is_err());
+        assert!(check_result(100, result(&["fjord"; 2], None)).is_err());
+        assert!(check_result(
+            100,
+            DailyResult {
+                solved: false,
+                ..result(&[answer], None)
+            }
+        )
+        .is_err());
+        assert!(check_result(
+            100,
+            DailyResult {
+                rows: vec![vec![CellValue::Correct('c')]],
+                solved: true,
+                seconds: None,
+            }
+        )
+        .is_err());
+        // rows have to be scored against the day's answer
+        assert!(check_result(101, result(&["slate", answer], None)).is_err());
+        assert!(check_result(100, scored("fjord", &["fjord"], None)).is_err());
+    }
+
+    #[test]
+    fn test_first_post_is_final() {
+        let mut group = Group::default();
+        assert!(group.post(100, "ann".into(), result(&["slate"; 6], None)));
+        assert!(!group.post(100, "ann".into(), result(&["crane"], Some(5))));
+        assert!(!group.results[&100]["ann"].solved);
+    }
+}
@@ -0,0 +1,33 @@
+//! A small server that keeps daily histories in sync between someone's devices,
+//! keeps leaderboards for groups of friends, and hosts head-to-head games. Run it
+//! anywhere the game can reach and point the game's sync setting, or a versus game,
+//! at it.
+//!
+//! Configured through the environment:
+//! - `PAUDLE_SERVER_ADDR`: where to listen, `127.0.0.1:8787` by default.
+//! - `PAUDLE_SERVER_DATA`: a JSON file to keep everything in. Without one nothing
+//!   is kept past a restart.
+mod api;
+mod db;
+mod group;
+mod round;
+mod versus;
+
+use std::{env, path::PathBuf};
+
+use tokio::net::TcpListener;
+
+const DEFAULT_ADDR: &str = "127.0.0.1:8787";
+
+#[tokio::main]
+async fn main() {
+    let addr = env::var("PAUDLE_SERVER_ADDR").unwrap_or_else(|_| DEFAULT_ADDR.to_string());
+    let data = env::var_os("PAUDLE_SERVER_DATA").map(PathBuf::from);
+    let db = db::Db::open(data).expect("Couldn't read the data file");
+    let listener = TcpListener::bind(&addr)
+        .await
+        .unwrap_or_else(|e| panic!("Couldn't listen on {}: {}", addr, e));
+    println!("Listening on http://{}", addr);
+    let app = api::router(db).merge(versus::router(versus::Rooms::default()));
+    axum::serve(listener, app).await.expect("Server stopped");
+}
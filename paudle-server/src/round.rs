@@ -0,0 +1,264 @@
+//! One round of a head-to-head game: the word, and everyone's guesses at it.
+use std::{
+    collections::BTreeMap,
+    time::{Duration, Instant},
+};
+
+use paudle_core::{
+    evaluate_guess, is_valid_guess,
+    versus::{hide_letters, PlayerView, Standing},
+    CellValue, Evaluation,
+};
+
+pub struct Round {
+    word: String,
+    max_guesses: usize,
+    players: BTreeMap<usize, Player>,
+}
+
+struct Player {
+    name: String,
+    rows: Vec<Evaluation>,
+    /// When they started on this word: the start of the round, or when they joined
+    /// if that was later.
+    started: Instant,
+    /// How long they took, once they've solved it or run out of guesses.
+    finished: Option<Duration>,
+    /// Whether they've lost their connection. Their rows and clock are kept for the
+    /// rest of the round, in case they come back.
+    away: bool,
+}
+
+impl Player {
+    fn solved(&self) -> bool {
+        self.rows
+            .last()
+            .is_some_and(|row| row.iter().all(|c| matches!(c, CellValue::Correct(_))))
+    }
+}
+
+impl Round {
+    pub fn new(word: impl Into<String>, max_guesses: usize) -> Self {
+        Self {
+            word: word.into(),
+            max_guesses,
+            players: BTreeMap::new(),
+        }
+    }
+
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    pub fn word_length(&self) -> usize {
+        self.word.chars().count()
+    }
+
+    pub fn max_guesses(&self) -> usize {
+        self.max_guesses
+    }
+
+    /// Whether nobody is connected.
+    pub fn is_empty(&self) -> bool {
+        self.players.values().all(|p| p.away)
+    }
+
+    /// Adds a player as `id`. Someone coming back under the name they left with
+    /// picks up where they were, rows and clock included, so reconnecting can't
+    /// buy a fresh start on the same word.
+    pub fn join(&mut self, id: usize, name: String, now: Instant) -> Result<(), String> {
+        let existing = self
+            .players
+            .iter()
+            .find(|(_, p)| p.name == name)
+            .map(|(id, p)| (*id, p.away));
+        let player = match existing {
+            Some((_, false)) => return Err(format!("Someone here is already called {}", name)),
+            Some((old_id, true)) => {
+                let mut player = self.players.remove(&old_id).expect("found above");
+                player.away = false;
+                player
+            }
+            None => Player {
+                name,
+                rows: Vec::new(),
+                started: now,
+                finished: None,
+                away: false,
+            },
+        };
+        self.players.insert(id, player);
+        Ok(())
+    }
+
+    pub fn leave(&mut self, id: usize) {
+        if let Some(player) = self.players.get_mut(&id) {
+            player.away = true;
+        }
+    }
+
+    /// Starts everyone still here on a new word.
+    pub fn restart(&mut self, word: impl Into<String>, now: Instant) {
+        self.word = word.into();
+        self.players.retain(|_, p| !p.away);
+        for player in self.players.values_mut() {
+            player.rows.clear();
+            player.started = now;
+            player.finished = None;
+        }
+    }
+
+    /// Scores a guess from player `id` with the same rules as every other game.
+    pub fn guess(&mut self, id: usize, guess: &str, now: Instant) -> Result<Evaluation, String> {
+        let player = self
+            .players
+            .get_mut(&id)
+            .ok_or("You're not in this round")?;
+        if player.finished.is_some() {
+            return Err("You've already finished".into());
+        }
+        let guess = guess.to_lowercase();
+        if guess.chars().count() != self.word.chars().count() {
+            return Err("Not enough letters".into());
+        }
+        if !is_valid_guess(&guess) {
+            return Err("Word not in word list".into());
+        }
+        let evaluation = evaluate_guess(&self.word, &guess);
+        player.rows.push(evaluation.clone());
+        if player.solved() || player.rows.len() >= self.max_guesses {
+            player.finished = Some(now.duration_since(player.started));
+        }
+        Ok(evaluation)
+    }
+
+    /// Player `id`'s scored rows, letters and all.
+    pub fn rows(&self, id: usize) -> &[Evaluation] {
+        self.players.get(&id).map_or(&[], |p| &p.rows)
+    }
+
+    /// Whether everyone still here has finished.
+    pub fn is_over(&self) -> bool {
+        !self.is_empty()
+            && self
+                .players
+                .values()
+                .all(|p| p.away || p.finished.is_some())
+    }
+
+    pub fn players(&self) -> Vec<PlayerView> {
+        self.players
+            .iter()
+            .map(|(id, player)| PlayerView {
+                id: *id,
+                name: player.name.clone(),
+                rows: player.rows.iter().map(|row| hide_letters(row)).collect(),
+                finished: player.finished.is_some(),
+            })
+            .collect()
+    }
+
+    /// Best first: whoever solved it in the fewest guesses, then the quickest. Those
+    /// who didn't solve it come last.
+    pub fn standings(&self) -> Vec<Standing> {
+        let mut standings = self
+            .players
+            .values()
+            .map(|player| Standing {
+                name: player.name.clone(),
+                guesses: player.solved().then_some(player.rows.len()),
+                millis: player.finished.map_or(u64::MAX, |d| {
+                    u64::try_from(d.as_millis()).unwrap_or(u64::MAX)
+                }),
+            })
+            .collect::<Vec<_>>();
+        standings.sort_by_key(|s| (s.guesses.is_none(), s.guesses, s.millis));
+        standings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use paudle_core::CellValue;
+
+    use super::Round;
+
+    #[test]
+    fn test_round() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut round = Round::new("crane", 3);
+        round.join(0, "ann".into(), start).unwrap();
+        round.join(1, "bob".into(), start).unwrap();
+        round.join(2, "cat".into(), at(5)).unwrap();
+        assert!(round.join(3, "cat".into(), at(5)).is_err());
+
+        assert!(round.guess(0, "paul", at(1)).is_err());
+        assert!(round.guess(0, "zzzzz", at(1)).is_err());
+        assert!(round.guess(3, "crane", at(1)).is_err());
+
+        round.guess(0, "slate", at(10)).unwrap();
+        assert_eq!(
+            round.guess(0, "CRANE", at(20)).unwrap()[0],
+            CellValue::Correct('c')
+        );
+        assert!(round.guess(0, "crane", at(21)).is_err());
+        // the same number of guesses, but cat started later so was quicker
+        round.guess(2, "moist", at(12)).unwrap();
+        round.guess(2, "crane", at(18)).unwrap();
+        for guess in ["slate", "moist"] {
+            round.guess(1, guess, at(30)).unwrap();
+        }
+        assert!(!round.is_over());
+
+        // nobody else sees any letters
+        let players = round.players();
+        assert_eq!(players[1].rows.len(), 2);
+        assert_eq!(players[1].rows[0][0], CellValue::Absent(' '));
+
+        round.guess(1, "abbey", at(40)).unwrap();
+        assert!(round.is_over());
+        let standings = round.standings();
+        let names = standings
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["cat", "ann", "bob"]);
+        assert_eq!(standings[0].millis, 13_000);
+        assert_eq!(standings[2].guesses, None);
+
+        round.restart("slate", at(50));
+        assert!(!round.is_over());
+        assert!(round.players().iter().all(|p| p.rows.is_empty()));
+    }
+
+    #[test]
+    fn test_rejoin() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        let mut round = Round::new("crane", 6);
+        round.join(0, "ann".into(), start).unwrap();
+        round.join(1, "bob".into(), start).unwrap();
+        round.guess(0, "slate", at(10)).unwrap();
+        round.leave(0);
+        assert!(!round.is_empty());
+        // bob finishing doesn't have to wait for ann while she's gone
+        round.guess(1, "crane", at(20)).unwrap();
+        assert!(round.is_over());
+
+        // back under the same name, with her rows and her clock still running
+        round.join(2, "ann".into(), at(100)).unwrap();
+        assert!(!round.is_over());
+        assert_eq!(round.players()[1].id, 2);
+        assert_eq!(round.players()[1].rows.len(), 1);
+        round.guess(2, "crane", at(110)).unwrap();
+        let ann = &round.standings()[1];
+        assert_eq!((ann.guesses, ann.millis), (Some(2), 110_000));
+
+        round.leave(1);
+        round.leave(2);
+        assert!(round.is_empty());
+    }
+}
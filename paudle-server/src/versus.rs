@@ -0,0 +1,349 @@
+//! Head-to-head rooms over WebSockets, at `/rooms/{room}/ws`. A room is made by the
+//! first player to join it and goes when the last one leaves. Messages are JSON
+//! [`ClientMessage`]s and [`ServerMessage`]s.
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::Response,
+    routing::get,
+    Router,
+};
+use paudle_core::{
+    schedule::scheduled_word,
+    versus::{ClientMessage, ServerMessage},
+    DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH,
+};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+
+use crate::round::Round;
+
+/// The word stream head-to-head words are drawn from, apart from the daily and
+/// multi-board ones.
+const VERSUS_STREAM: u64 = 0x7673;
+
+#[derive(Clone, Default)]
+pub struct Rooms(Arc<Mutex<HashMap<String, Room>>>);
+
+impl Rooms {
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, Room>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+struct Room {
+    round: Round,
+    /// Where to send each player's messages.
+    players: HashMap<usize, UnboundedSender<ServerMessage>>,
+    next_id: usize,
+}
+
+impl Room {
+    fn new() -> Self {
+        Self {
+            round: Round::new(random_word(), DEFAULT_MAX_GUESSES),
+            players: HashMap::new(),
+            next_id: 0,
+        }
+    }
+
+    fn send(&self, id: usize, message: ServerMessage) {
+        if let Some(player) = self.players.get(&id) {
+            // A closed channel means they're on their way out
+            let _ = player.send(message);
+        }
+    }
+
+    fn broadcast(&self, message: &ServerMessage) {
+        for player in self.players.values() {
+            let _ = player.send(message.clone());
+        }
+    }
+
+    /// Starts player `id` on the round, with any rows they had before they lost
+    /// their connection.
+    fn send_round(&self, id: usize) {
+        self.send(
+            id,
+            ServerMessage::Round {
+                word_length: self.round.word_length(),
+                max_guesses: self.round.max_guesses(),
+                you: id,
+            },
+        );
+        for row in self.round.rows(id) {
+            self.send(id, ServerMessage::Scored(row.clone()));
+        }
+    }
+
+    /// Tells everyone where things stand, and how it ended if it has.
+    fn update_players(&self) {
+        self.broadcast(&ServerMessage::Players(self.round.players()));
+        if self.round.is_over() {
+            self.broadcast(&ServerMessage::Finished {
+                word: self.round.word().to_string(),
+                standings: self.round.standings(),
+            });
+        }
+    }
+}
+
+fn random_word() -> &'static str {
+    let seed = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_nanos() as u64);
+    scheduled_word(DEFAULT_WORD_LENGTH, VERSUS_STREAM, seed).expect("there are five letter words")
+}
+
+pub fn router(rooms: Rooms) -> Router {
+    Router::new()
+        .route("/rooms/{room}/ws", get(connect))
+        .with_state(rooms)
+}
+
+async fn connect(
+    ws: WebSocketUpgrade,
+    Path(room): Path<String>,
+    State(rooms): State<Rooms>,
+) -> Response {
+    ws.on_upgrade(move |socket| play(socket, room, rooms))
+}
+
+async fn play(mut socket: WebSocket, room_name: String, rooms: Rooms) {
+    let name = loop {
+        match receive(&mut socket).await {
+            Some(Ok(ClientMessage::Join { name })) => break name,
+            Some(_) => continue,
+            None => return,
+        }
+    };
+    let (tx, mut rx) = unbounded_channel();
+    let joined = {
+        let mut rooms = rooms.lock();
+        let room = rooms.entry(room_name.clone()).or_insert_with(Room::new);
+        let id = room.next_id;
+        room.next_id += 1;
+        room.round.join(id, name, Instant::now()).map(|()| {
+            room.players.insert(id, tx);
+            room.send_round(id);
+            room.update_players();
+            id
+        })
+    };
+    let id = match joined {
+        Ok(id) => id,
+        Err(e) => {
+            // Taking someone else's place isn't allowed, so that's the end of it
+            let reply =
+                serde_json::to_string(&ServerMessage::Rejected(e)).expect("messages serialize");
+            let _ = socket.send(Message::Text(reply.into())).await;
+            return;
+        }
+    };
+
+    loop {
+        tokio::select! {
+            outgoing = rx.recv() => {
+                let Some(message) = outgoing else { break };
+                let text = serde_json::to_string(&message).expect("messages serialize");
+                if socket.send(Message::Text(text.into())).await.is_err() {
+                    break;
+                }
+            }
+            incoming = receive(&mut socket) => match incoming {
+                Some(Ok(message)) => handle(&rooms, &room_name, id, message),
+                Some(Err(e)) => {
+                    let reply = serde_json::to_string(&ServerMessage::Rejected(e)).expect("messages serialize");
+                    if socket.send(Message::Text(reply.into())).await.is_err() {
+                        break;
+                    }
+                }
+                None => break,
+            },
+        }
+    }
+
+    let mut rooms = rooms.lock();
+    if let Some(room) = rooms.get_mut(&room_name) {
+        room.players.remove(&id);
+        room.round.leave(id);
+        if room.round.is_empty() {
+            rooms.remove(&room_name);
+        } else {
+            room.update_players();
+        }
+    }
+}
+
+/// The next message from the player, `None` once they've gone.
+async fn receive(socket: &mut WebSocket) -> Option<Result<ClientMessage, String>> {
+    loop {
+        match socket.recv().await? {
+            Ok(Message::Text(text)) => {
+                return Some(serde_json::from_str(text.as_str()).map_err(|e| e.to_string()))
+            }
+            Ok(Message::Close(_)) | Err(_) => return None,
+            Ok(_) => {}
+        }
+    }
+}
+
+fn handle(rooms: &Rooms, room_name: &str, id: usize, message: ClientMessage) {
+    let mut rooms = rooms.lock();
+    let Some(room) = rooms.get_mut(room_name) else {
+        return;
+    };
+    match message {
+        ClientMessage::Join { .. } => {}
+        ClientMessage::Guess(guess) => match room.round.guess(id, &guess, Instant::now()) {
+            Ok(evaluation) => {
+                room.send(id, ServerMessage::Scored(evaluation));
+                room.update_players();
+            }
+            Err(e) => room.send(id, ServerMessage::Rejected(e)),
+        },
+        ClientMessage::Rematch => {
+            if room.round.is_over() {
+                room.round.restart(random_word(), Instant::now());
+                let ids = room.players.keys().copied().collect::<Vec<_>>();
+                ids.into_iter().for_each(|id| room.send_round(id));
+                room.update_players();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use futures_util::{SinkExt, StreamExt};
+    use paudle_core::{
+        evaluate_guess,
+        versus::{ClientMessage, ServerMessage},
+    };
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_tungstenite::{connect_async, tungstenite, MaybeTlsStream, WebSocketStream};
+
+    use crate::round::Round;
+
+    use super::{router, Room, Rooms};
+
+    type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    /// Serves a room whose word is "crane", and returns its address.
+    async fn serve() -> String {
+        let rooms = Rooms::default();
+        rooms.lock().insert(
+            "test".into(),
+            Room {
+                round: Round::new("crane", 6),
+                players: HashMap::new(),
+                next_id: 0,
+            },
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(rooms)).await.unwrap() });
+        format!("ws://{}/rooms/test/ws", addr)
+    }
+
+    async fn send(socket: &mut Socket, message: ClientMessage) {
+        let text = serde_json::to_string(&message).unwrap();
+        socket
+            .send(tungstenite::Message::Text(text.into()))
+            .await
+            .unwrap();
+    }
+
+    async fn join(url: &str, name: &str) -> Socket {
+        let (mut socket, _) = connect_async(url).await.unwrap();
+        send(&mut socket, ClientMessage::Join { name: name.into() }).await;
+        socket
+    }
+
+    /// The next message, or `None` once the server has hung up.
+    async fn receive(socket: &mut Socket) -> Option<ServerMessage> {
+        loop {
+            match socket.next().await? {
+                Ok(tungstenite::Message::Text(text)) => {
+                    return Some(serde_json::from_str(text.as_str()).unwrap())
+                }
+                Ok(tungstenite::Message::Close(_)) | Err(_) => return None,
+                Ok(_) => {}
+            }
+        }
+    }
+
+    /// The next message that isn't just where everyone has got to.
+    async fn next(socket: &mut Socket) -> ServerMessage {
+        loop {
+            match receive(socket).await.unwrap() {
+                ServerMessage::Players(_) => {}
+                message => return message,
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejoining_keeps_progress() {
+        let url = serve().await;
+        let mut ann = join(&url, "ann").await;
+        assert!(matches!(
+            next(&mut ann).await,
+            ServerMessage::Round { you: 0, .. }
+        ));
+        send(&mut ann, ClientMessage::Guess("slate".into())).await;
+        let slate = evaluate_guess("crane", "slate");
+        assert_eq!(next(&mut ann).await, ServerMessage::Scored(slate.clone()));
+
+        let mut bob = join(&url, "bob").await;
+        assert!(matches!(
+            next(&mut bob).await,
+            ServerMessage::Round { you: 1, .. }
+        ));
+        assert!(matches!(
+            receive(&mut bob).await,
+            Some(ServerMessage::Players(_))
+        ));
+        // nobody can take a name that's playing
+        let mut impostor = join(&url, "bob").await;
+        assert!(matches!(
+            next(&mut impostor).await,
+            ServerMessage::Rejected(_)
+        ));
+        assert_eq!(receive(&mut impostor).await, None);
+
+        // bob hears about ann leaving, and she comes back to the board she left
+        ann.close(None).await.unwrap();
+        assert!(matches!(
+            receive(&mut bob).await,
+            Some(ServerMessage::Players(_))
+        ));
+        let mut ann = join(&url, "ann").await;
+        assert!(matches!(
+            next(&mut ann).await,
+            ServerMessage::Round { you: 3, .. }
+        ));
+        assert_eq!(next(&mut ann).await, ServerMessage::Scored(slate));
+
+        send(&mut ann, ClientMessage::Guess("crane".into())).await;
+        assert!(matches!(next(&mut ann).await, ServerMessage::Scored(_)));
+        send(&mut bob, ClientMessage::Guess("crane".into())).await;
+        assert!(matches!(next(&mut bob).await, ServerMessage::Scored(_)));
+        let ServerMessage::Finished { word, standings } = next(&mut bob).await else {
+            panic!("the round should be over");
+        };
+        assert_eq!(word, "crane");
+        // ann's first guess still counts
+        assert_eq!(standings[0].name, "bob");
+        assert_eq!(standings[1].guesses, Some(2));
+    }
+}
@@ -0,0 +1,195 @@
+use gloo_events::EventListener;
+use patternfly_yew::{Backdrop, BackdropDispatcher, Bullseye, Modal, ModalVariant};
+use paudle_core::{
+    answer_list, AbsurdleGame, GuessError, DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH,
+};
+use yew::prelude::*;
+
+use crate::{
+    board::Board,
+    handle_keypress,
+    keyboard::{Keyboard, KeyboardStatus},
+    listen_for_keys,
+    save::{load_absurdle_history, load_absurdle_saved_state, update_absurdle_saved_state},
+    scoreboard::{
+        generate_absurdle_score_copy, share_formats, ModeStats, Scoreboard, ScoreboardFooter, Stats,
+    },
+    settings::{load_settings, update_settings, Settings},
+    store::{SaveStore, Store},
+    toast_error, PaudleMsg,
+};
+
+pub struct AbsurdlePaudle {
+    pub(crate) game: AbsurdleGame,
+    keyboard_status: KeyboardStatus,
+    store: Store,
+    keydown_listener: Option<EventListener>,
+    storage_listener: Option<EventListener>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct AbsurdlePaudleProps {
+    pub store: Store,
+}
+
+impl AbsurdlePaudle {
+    pub fn new(store: Store) -> Self {
+        Self {
+            game: AbsurdleGame::new(answer_list(DEFAULT_WORD_LENGTH).unwrap().lines()),
+            keyboard_status: KeyboardStatus::default(),
+            store,
+            keydown_listener: None,
+            storage_listener: None,
+        }
+    }
+
+    /// Swaps in a new game, keeping the page's listeners attached.
+    fn replace_game(&mut self, mut new_game: AbsurdlePaudle) {
+        new_game.keydown_listener = self.keydown_listener.take();
+        new_game.storage_listener = self.storage_listener.take();
+        *self = new_game;
+    }
+
+    /// Carries on from another tab's game if it got further than this one.
+    fn merge_saved(&mut self, _ctx: &Context<Self>) -> bool {
+        match load_absurdle_saved_state(&self.store) {
+            Some(saved_state) if saved_state.guesses.len() > self.game.guesses().len() => {
+                self.replace_game(AbsurdlePaudle::from_saved(saved_state, self.store.clone()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn add_guess(&mut self, guess: &str) {
+        let evaluation = self.game.add_guess(guess);
+        self.keyboard_status.update_status(&evaluation);
+    }
+
+    fn row_count(&self) -> usize {
+        let rows = self.game.guesses().len() + usize::from(!self.game.is_over());
+        rows.max(DEFAULT_MAX_GUESSES)
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn show_scoreboard(&self, ctx: &Context<Self>) {
+        let clear = ctx.link().callback(|msg: PaudleMsg| msg);
+        let guesses = self.game.guesses();
+        let share_texts = share_formats(|format| generate_absurdle_score_copy(guesses, format));
+        let history = load_absurdle_history(&self.store);
+        // There's no limit on guesses, so the bars go as far as the longest game
+        let bar_count = history
+            .scores
+            .values()
+            .map(|game| game.guesses.len())
+            .chain([guesses.len(), DEFAULT_MAX_GUESSES])
+            .max()
+            .unwrap_or(DEFAULT_MAX_GUESSES);
+        let modes = vec![ModeStats {
+            label: "Absurdle".to_string(),
+            caption: "Absurdle".to_string(),
+            stats: Stats::from_history(&history, 1..=bar_count),
+            highlight: Some(guesses.len()),
+        }];
+        let bd = Backdrop {
+            content: html! {
+                <Bullseye>
+                    <Modal
+                        title={"Cornered it!".to_string()}
+                        variant={ModalVariant::Small}
+                        footer={Some(html!{<ScoreboardFooter
+                                                share_texts={share_texts}
+                                                format={load_settings().share_format}
+                                                clear={clear}
+                                            />})}
+                    >
+                        {format!("Solved in {} guesses.", guesses.len())}
+                        <Scoreboard modes={modes} />
+                    </Modal>
+                </Bullseye>
+            },
+        };
+        BackdropDispatcher::default().open(bd);
+    }
+}
+
+impl Component for AbsurdlePaudle {
+    type Message = PaudleMsg;
+
+    type Properties = AbsurdlePaudleProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = ctx.props().store.clone();
+        match load_absurdle_saved_state(&store) {
+            Some(saved_state) => AbsurdlePaudle::from_saved(saved_state, store),
+            None => AbsurdlePaudle::new(store),
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match (self.game.is_over(), msg) {
+            (false, PaudleMsg::TypeLetter(c)) => self.game.type_letter(c),
+            (false, PaudleMsg::Backspace) => self.game.backspace(),
+            (false, PaudleMsg::Submit) => match self.game.submit() {
+                Ok(evaluation) => {
+                    self.keyboard_status.update_status(&evaluation);
+                    update_absurdle_saved_state(&self.store, self);
+                    if self.game.is_over() {
+                        self.show_scoreboard(ctx);
+                    }
+                    true
+                }
+                Err(e @ GuessError::NotInWordList) => {
+                    toast_error(e.to_string());
+                    true
+                }
+                Err(_) => false,
+            },
+            (true, PaudleMsg::StartRandom) => {
+                self.replace_game(AbsurdlePaudle::new(self.store.clone()));
+                true
+            }
+            (_, PaudleMsg::SetShareFormat(share_format)) => {
+                update_settings(&Settings {
+                    share_format,
+                    ..load_settings()
+                });
+                false
+            }
+            (_, PaudleMsg::StorageChanged) => self.merge_saved(ctx),
+            (_, PaudleMsg::Escape) => {
+                BackdropDispatcher::default().close();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let cb = ctx.link().callback(|msg: PaudleMsg| msg);
+
+        html! {
+            <>
+                <Board
+                    current_guess={self.game.current_guess().to_string()}
+                    guesses={self.game.guesses().to_vec()}
+                    row_count={self.row_count()}
+                    word_length={self.game.word_length()}
+                />
+                <Keyboard key_press={cb} keys={self.keyboard_status.clone()} />
+            </>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        self.keydown_listener = Some(listen_for_keys(ctx.link().batch_callback(handle_keypress)));
+        self.storage_listener = self
+            .store
+            .watch(ctx.link().callback(|_| PaudleMsg::StorageChanged));
+    }
+}
@@ -0,0 +1,49 @@
+use paudle_core::GuessAnalysis;
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct AnalysisProps {
+    pub rows: Vec<GuessAnalysis>,
+}
+
+/// Per-guess breakdown shown under the scoreboard: how far each guess narrowed the
+/// answers down and how it compares with the solver's pick from the same spot.
+#[function_component(Analysis)]
+pub fn analysis(props: &AnalysisProps) -> Html {
+    let rows = props.rows.iter().map(|row| {
+        html! {
+            <tr>
+                <td class="analysis-guess">{row.guess.to_uppercase()}</td>
+                <td>{format!("{} → {}", row.candidates_before, row.candidates_after)}</td>
+                <td>{format!("{:.1} / {:.1}", row.expected_information, row.best_information)}</td>
+                <td class="analysis-guess">
+                    {row.best_guess.as_deref().unwrap_or("-").to_uppercase()}
+                </td>
+                <td>{format!("{:.0}", row.skill())}</td>
+                <td>{format!("{:+.1}", row.luck())}</td>
+            </tr>
+        }
+    });
+    html! {
+        <div class="analysis">
+            <div class="scoreboard-header">{"Analysis"}</div>
+            <table>
+                <thead>
+                    <tr>
+                        <th>{"Guess"}</th>
+                        <th>{"Words left"}</th>
+                        <th title="Expected bits of information for your guess and the best guess">
+                            {"Bits"}
+                        </th>
+                        <th>{"Best"}</th>
+                        <th>{"Skill"}</th>
+                        <th title="Bits revealed beyond what was expected">{"Luck"}</th>
+                    </tr>
+                </thead>
+                <tbody>
+                    { for rows }
+                </tbody>
+            </table>
+        </div>
+    }
+}
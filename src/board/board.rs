@@ -1,7 +1,7 @@
-use super::row::Row;
+use paudle_core::CellValue;
 use yew::prelude::*;
 
-use super::cell::CellValue;
+use super::row::Row;
 
 #[derive(Properties, PartialEq)]
 pub struct BoardProps {
@@ -27,7 +27,7 @@ pub fn view(props: &BoardProps) -> Html {
     }
     html! {
             <div class="wrapper">
-                <div class="game">
+                <div class="game" style={format!("--word-length: {}", props.word_length)}>
                     {
                         rows.into_iter()
                             .map(|r| {
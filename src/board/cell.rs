@@ -1,26 +1,5 @@
-use serde::{Deserialize, Serialize};
-use yew::{html::ImplicitClone, prelude::*};
-
-#[derive(Debug, PartialEq, Clone, Copy, Serialize, Deserialize)]
-pub enum CellValue {
-    Empty,
-    Typing(char),
-    Absent(char),
-    Present(char),
-    Correct(char),
-}
-
-impl CellValue {
-    pub fn score_char(self) -> char {
-        match self {
-            Self::Empty | Self::Typing(_) | Self::Absent(_) => '⬜',
-            Self::Present(_) => '🟨',
-            Self::Correct(_) => '🟩',
-        }
-    }
-}
-
-impl ImplicitClone for CellValue {}
+use paudle_core::CellValue;
+use yew::prelude::*;
 
 #[derive(Properties, PartialEq, Clone)]
 pub struct CellProps {
@@ -4,4 +4,3 @@ mod cell;
 mod row;
 
 pub use board::Board;
-pub use cell::CellValue;
@@ -1,6 +1,8 @@
 use yew::prelude::*;
 
-use super::cell::{Cell, CellValue};
+use paudle_core::CellValue;
+
+use super::cell::Cell;
 
 #[derive(Properties, PartialEq)]
 pub struct RowProps {
@@ -21,7 +23,7 @@ impl Component for Row {
     fn view(&self, ctx: &Context<Self>) -> Html {
         html! {
             <div class="row">
-                { ctx.props().values.clone().iter().map(|c| html! { <Cell value={c} /> }).collect::<Html>() }
+                { ctx.props().values.iter().map(|c| html! { <Cell value={*c} /> }).collect::<Html>() }
             </div>
         }
     }
@@ -0,0 +1,248 @@
+use paudle_core::{is_valid_guess, DEFAULT_WORD_LENGTH, WORD_LENGTHS};
+use web_sys::{HtmlInputElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::{
+    links::{current_query, guesses_param, page_url, parse_guesses_param, query_pairs},
+    scoreboard::copy_to_clipboard,
+    settings::default_max_guesses,
+    GUESS_LIMITS,
+};
+
+/// Not meant to stop anyone determined, just to keep the answer from being read
+/// straight out of the link.
+const OBFUSCATION_KEY: &[u8] = b"paudle";
+
+/// A puzzle someone made for their friends, as read from a link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomPuzzle {
+    /// The answer as it appears in the link.
+    pub code: String,
+    pub word: String,
+    pub max_guesses: Option<usize>,
+}
+
+pub fn encode_word(word: &str) -> String {
+    word.bytes()
+        .zip(OBFUSCATION_KEY.iter().cycle())
+        .enumerate()
+        .map(|(idx, (b, k))| format!("{:02x}", (b ^ k).wrapping_add((idx as u8).wrapping_mul(31))))
+        .collect()
+}
+
+/// Reverses [`encode_word`], returning `None` if `code` doesn't decode to a
+/// playable word.
+pub fn decode_word(code: &str) -> Option<String> {
+    if code.len() > 2 * WORD_LENGTHS.end() {
+        return None;
+    }
+    let word = unscramble(code)?;
+    is_valid_guess(&word).then_some(word)
+}
+
+/// Undoes the hex and the key, whatever the result spells.
+fn unscramble(code: &str) -> Option<String> {
+    if !code.len().is_multiple_of(2) || !code.is_ascii() {
+        return None;
+    }
+    let bytes = (0..code.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&code[idx..idx + 2], 16).ok())
+        .collect::<Option<Vec<_>>>()?;
+    Some(
+        bytes
+            .into_iter()
+            .zip(OBFUSCATION_KEY.iter().cycle())
+            .enumerate()
+            .map(|(idx, (b, k))| char::from(b.wrapping_sub((idx as u8).wrapping_mul(31)) ^ k))
+            .collect(),
+    )
+}
+
+/// Checks a word someone wants to make a puzzle out of and encodes it.
+fn check_puzzle_word(word: &str, word_length: usize) -> Result<String, String> {
+    let word = word.trim().to_lowercase();
+    if word.chars().count() != word_length {
+        return Err(format!("Puzzle words need {} letters", word_length));
+    }
+    if !is_valid_guess(&word) {
+        return Err("Word not in word list".to_string());
+    }
+    Ok(encode_word(&word))
+}
+
+pub fn puzzle_query(code: &str, max_guesses: Option<usize>) -> String {
+    format!("puzzle={}&guesses={}", code, guesses_param(max_guesses))
+}
+
+/// The link that opens the puzzle `code` with the given guess limit.
+pub fn puzzle_url(code: &str, max_guesses: Option<usize>) -> String {
+    page_url(&puzzle_query(code, max_guesses))
+}
+
+/// The puzzle in the page's query string, if it was opened from a puzzle link.
+pub fn puzzle_from_url() -> Option<CustomPuzzle> {
+    parse_puzzle_query(&current_query())
+}
+
+fn parse_puzzle_query(search: &str) -> Option<CustomPuzzle> {
+    let mut code = None;
+    let mut max_guesses = default_max_guesses();
+    for (key, value) in query_pairs(search) {
+        match key {
+            "puzzle" => code = Some(value.to_string()),
+            "guesses" => max_guesses = parse_guesses_param(value),
+            _ => {}
+        }
+    }
+    let code = code?;
+    Some(CustomPuzzle {
+        word: decode_word(&code)?,
+        code,
+        max_guesses,
+    })
+}
+
+#[function_component(CreatePuzzle)]
+pub fn create_puzzle() -> Html {
+    let word = use_state(String::new);
+    let word_length = use_state(|| DEFAULT_WORD_LENGTH);
+    let max_guesses = use_state(default_max_guesses);
+    let link = use_state(|| None::<Result<String, String>>);
+    let copy_label = use_state(|| "Copy link".to_string());
+
+    let set_word = {
+        let word = word.clone();
+        Callback::from(move |e: InputEvent| {
+            word.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let set_word_length = {
+        let word_length = word_length.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            if let Ok(len) = select.value().parse() {
+                word_length.set(len);
+            }
+        })
+    };
+    let set_max_guesses = {
+        let max_guesses = max_guesses.clone();
+        Callback::from(move |e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            match select.value().as_str() {
+                "unlimited" => max_guesses.set(None),
+                value => {
+                    if let Ok(max) = value.parse() {
+                        max_guesses.set(Some(max));
+                    }
+                }
+            }
+        })
+    };
+    let create = {
+        let word = word.clone();
+        let word_length = word_length.clone();
+        let max_guesses = max_guesses.clone();
+        let link = link.clone();
+        let copy_label = copy_label.clone();
+        Callback::from(move |_: MouseEvent| {
+            let url =
+                check_puzzle_word(&word, *word_length).map(|code| puzzle_url(&code, *max_guesses));
+            link.set(Some(url));
+            copy_label.set("Copy link".to_string());
+        })
+    };
+
+    let result = match &*link {
+        Some(Ok(url)) => {
+            let shown = url.clone();
+            let url = url.clone();
+            let copy_label_cb = copy_label.clone();
+            let copy = Callback::from(move |_: MouseEvent| {
+                let url = url.clone();
+                wasm_bindgen_futures::spawn_local(async move {
+                    copy_to_clipboard(url).await.unwrap();
+                });
+                copy_label_cb.set("Copied!".to_string());
+            });
+            html! {
+                <div class="puzzle-link">
+                    <input type="text" readonly=true value={shown} />
+                    <span class="play-button" onclick={copy}>{&*copy_label}</span>
+                </div>
+            }
+        }
+        Some(Err(e)) => html! { <div class="puzzle-error">{e}</div> },
+        None => html! {},
+    };
+
+    html! {
+        <div class="create-puzzle">
+            <input
+                type="text"
+                placeholder="Answer"
+                value={(*word).clone()}
+                oninput={set_word}
+            />
+            <select onchange={set_word_length}>
+                { for WORD_LENGTHS.map(|len| html! {
+                    <option value={len.to_string()} selected={len == *word_length}>
+                        {format!("{} letters", len)}
+                    </option>
+                }) }
+            </select>
+            <select onchange={set_max_guesses}>
+                { for GUESS_LIMITS.map(|max| html! {
+                    <option value={max.to_string()} selected={Some(max) == *max_guesses}>
+                        {format!("{} guesses", max)}
+                    </option>
+                }) }
+                <option value="unlimited" selected={max_guesses.is_none()}>
+                    {"Unlimited guesses"}
+                </option>
+            </select>
+            <span class="play-button" onclick={create}>{"Create link"}</span>
+            {result}
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_puzzle_word, decode_word, encode_word, parse_puzzle_query, unscramble};
+
+    #[test]
+    fn test_encoding() {
+        for word in ["crane", "abbey", "planet", "zymurgy"] {
+            let code = encode_word(word);
+            assert!(!code.contains(word));
+            assert_eq!(decode_word(&code).as_deref(), Some(word));
+        }
+        assert_eq!(decode_word("not hex"), None);
+        assert_eq!(decode_word(&encode_word("zzzzz")), None);
+        assert_eq!(decode_word("abc"), None);
+    }
+
+    #[test]
+    fn test_long_codes() {
+        let long = "abcdefghijklmnopqrstuvwxyz".repeat(12);
+        let code = encode_word(&long);
+        assert_eq!(unscramble(&code).as_deref(), Some(long.as_str()));
+        // far longer than any word, so it's turned away before decoding
+        assert_eq!(decode_word(&code), None);
+        assert_eq!(decode_word(&encode_word("zymurgyzz")), None);
+    }
+
+    #[test]
+    fn test_puzzle_query() {
+        let code = check_puzzle_word(" Crane ", 5).unwrap();
+        let puzzle = parse_puzzle_query(&format!("?puzzle={}&guesses=unlimited", code)).unwrap();
+        assert_eq!(puzzle.word, "crane");
+        assert_eq!(puzzle.max_guesses, None);
+        let puzzle = parse_puzzle_query(&format!("?puzzle={}", code)).unwrap();
+        assert_eq!(puzzle.max_guesses, Some(6));
+        assert!(check_puzzle_word("crane", 6).is_err());
+        assert!(parse_puzzle_query("?guesses=4").is_none());
+    }
+}
@@ -0,0 +1,293 @@
+#![allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+//! The stats page: everything we can work out from a player's history.
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::Datelike;
+use paudle_core::{puzzle_date, puzzle_number};
+use yew::prelude::*;
+
+use crate::{
+    save::{GameHistory, Outcome, SaveState},
+    GameMode,
+};
+
+/// How many games each point on the trend chart averages over.
+const TREND_WINDOW: usize = 10;
+/// How many weeks of daily results the calendar shows.
+const CALENDAR_WEEKS: i64 = 15;
+const TOP_OPENERS: usize = 5;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Dashboard {
+    /// Average guesses taken over the games that were won.
+    pub average_guesses: Option<f32>,
+    /// How each daily puzzle went: the guesses it took, or `None` if it was lost.
+    pub results: BTreeMap<u32, Option<usize>>,
+    /// First guesses and how often they were used, most used first.
+    pub openers: Vec<(String, usize)>,
+    /// How often each letter was guessed, most used first.
+    pub letters: Vec<(char, usize)>,
+    /// Average seconds from the first letter typed to the winning guess, for games
+    /// that were timed.
+    pub average_solve_time: Option<i64>,
+    /// Rolling average of the guesses taken, oldest first. A lost game counts as
+    /// one guess more than it was played for.
+    pub trend: Vec<f32>,
+}
+
+impl Dashboard {
+    pub fn from_history(history: &GameHistory<SaveState>) -> Self {
+        let mut games = history.scores.iter().collect::<Vec<_>>();
+        games.sort_unstable_by_key(|(number, _)| **number);
+
+        let wins = games
+            .iter()
+            .filter(|(_, game)| game.was_won())
+            .map(|(_, game)| game.guess_count())
+            .collect::<Vec<_>>();
+        let average_guesses =
+            (!wins.is_empty()).then(|| wins.iter().sum::<usize>() as f32 / wins.len() as f32);
+
+        let results = games
+            .iter()
+            .filter(|(_, game)| matches!(game.game_mode, GameMode::Daily(_)))
+            .map(|(number, game)| (**number, game.was_won().then(|| game.guess_count())))
+            .collect();
+
+        let mut openers = HashMap::new();
+        let mut letters = HashMap::new();
+        for (_, game) in &games {
+            let mut words = game
+                .guesses
+                .iter()
+                .map(|guess| guess.iter().filter_map(|c| c.letter()).collect::<String>());
+            if let Some(opener) = words.next() {
+                letters.extend_counts(opener.chars());
+                *openers.entry(opener).or_insert(0) += 1;
+            }
+            words.for_each(|word| letters.extend_counts(word.chars()));
+        }
+
+        let solve_times = games
+            .iter()
+            .filter(|(_, game)| game.was_won())
+            .filter_map(|(_, game)| Some(game.finished_at? - game.started_at?))
+            .collect::<Vec<_>>();
+        let average_solve_time = (!solve_times.is_empty())
+            .then(|| solve_times.iter().sum::<i64>() / solve_times.len() as i64);
+
+        let scores = games
+            .iter()
+            .map(|(_, game)| game.guess_count() + usize::from(!game.was_won()))
+            .collect::<Vec<_>>();
+        let trend = (0..scores.len())
+            .map(|idx| {
+                let window = &scores[(idx + 1).saturating_sub(TREND_WINDOW)..=idx];
+                window.iter().sum::<usize>() as f32 / window.len() as f32
+            })
+            .collect();
+
+        Self {
+            average_guesses,
+            results,
+            openers: most_used(openers).into_iter().take(TOP_OPENERS).collect(),
+            letters: most_used(letters),
+            average_solve_time,
+            trend,
+        }
+    }
+}
+
+trait CountExt<T> {
+    fn extend_counts(&mut self, items: impl Iterator<Item = T>);
+}
+
+impl<T: std::hash::Hash + Eq> CountExt<T> for HashMap<T, usize> {
+    fn extend_counts(&mut self, items: impl Iterator<Item = T>) {
+        items.for_each(|item| *self.entry(item).or_insert(0) += 1);
+    }
+}
+
+/// Most used first, ties in their natural order.
+fn most_used<T: Ord>(counts: HashMap<T, usize>) -> Vec<(T, usize)> {
+    let mut counts = counts.into_iter().collect::<Vec<_>>();
+    counts.sort_unstable_by(|(a, a_count), (b, b_count)| b_count.cmp(a_count).then(a.cmp(b)));
+    counts
+}
+
+pub(crate) fn format_duration(secs: i64) -> String {
+    if secs < 60 {
+        format!("{}s", secs)
+    } else if secs < 60 * 60 {
+        format!("{}m {}s", secs / 60, secs % 60)
+    } else {
+        format!("{}h {}m", secs / 3600, secs / 60 % 60)
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct StatsDashboardProps {
+    pub dashboard: Dashboard,
+    /// Today's puzzle, where the calendar ends.
+    pub today: u32,
+}
+
+#[function_component(StatsDashboard)]
+pub fn stats_dashboard(props: &StatsDashboardProps) -> Html {
+    let dashboard = &props.dashboard;
+    html! {
+        <div class="dashboard">
+            <div class="dashboard-summary">
+                <div>
+                    <div>{dashboard.average_guesses.map_or_else(|| "-".to_string(), |avg| format!("{:.2}", avg))}</div>
+                    <div>{"Average guesses"}</div>
+                </div>
+                <div>
+                    <div>{dashboard.average_solve_time.map_or_else(|| "-".to_string(), format_duration)}</div>
+                    <div>{"Average time to solve"}</div>
+                </div>
+            </div>
+            if !dashboard.results.is_empty() {
+                <div class="scoreboard-header">{"Daily Results"}</div>
+                <Calendar results={dashboard.results.clone()} today={props.today} />
+            }
+            if dashboard.trend.len() > 1 {
+                <div class="scoreboard-header">{format!("Trend ({}-game average)", TREND_WINDOW)}</div>
+                <TrendChart trend={dashboard.trend.clone()} />
+            }
+            if !dashboard.openers.is_empty() {
+                <div class="scoreboard-header">{"Favourite Openers"}</div>
+                <div class="dashboard-openers">
+                    { for dashboard.openers.iter().map(|(word, count)| html! {
+                        <div><span class="dashboard-opener">{word}</span>{format!(" ×{}", count)}</div>
+                    }) }
+                </div>
+            }
+            if !dashboard.letters.is_empty() {
+                <div class="scoreboard-header">{"Letters Guessed"}</div>
+                <div class="dashboard-letters">
+                    { for dashboard.letters.iter().map(|(letter, count)| html! {
+                        <div class="dashboard-letter">
+                            <div>{letter.to_ascii_uppercase()}</div>
+                            <div>{count}</div>
+                        </div>
+                    }) }
+                </div>
+            }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct CalendarProps {
+    results: BTreeMap<u32, Option<usize>>,
+    today: u32,
+}
+
+/// The last few weeks of daily puzzles, a column a week starting on Monday.
+#[function_component(Calendar)]
+fn calendar(props: &CalendarProps) -> Html {
+    let today = puzzle_date(props.today);
+    let start = today
+        - chrono::Duration::days(
+            i64::from(today.weekday().num_days_from_monday()) + (CALENDAR_WEEKS - 1) * 7,
+        );
+    let days = (0..CALENDAR_WEEKS * 7)
+        .map(|offset| start + chrono::Duration::days(offset))
+        .take_while(|date| *date <= today);
+    html! {
+        <div class="calendar">
+            { for days.map(|date| {
+                let puzzle = puzzle_number(date);
+                let result = puzzle.and_then(|p| props.results.get(&p));
+                let (class, title) = match (puzzle, result) {
+                    (Some(p), Some(Some(guesses))) => (
+                        format!("calendar-day won guesses-{}", guesses.min(&7)),
+                        format!("#{}: {} guesses", p, guesses),
+                    ),
+                    (Some(p), Some(None)) => ("calendar-day lost".to_string(), format!("#{}: lost", p)),
+                    (Some(p), None) => ("calendar-day".to_string(), format!("#{}", p)),
+                    (None, _) => ("calendar-day".to_string(), String::new()),
+                };
+                html! { <div class={class} title={title} /> }
+            }) }
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct TrendChartProps {
+    trend: Vec<f32>,
+}
+
+#[function_component(TrendChart)]
+fn trend_chart(props: &TrendChartProps) -> Html {
+    const WIDTH: f32 = 300.;
+    const HEIGHT: f32 = 80.;
+    let max = props.trend.iter().copied().fold(1., f32::max);
+    let step = WIDTH / (props.trend.len() - 1) as f32;
+    let points = props
+        .trend
+        .iter()
+        .enumerate()
+        .map(|(idx, avg)| {
+            format!(
+                "{:.1},{:.1}",
+                idx as f32 * step,
+                HEIGHT - avg / max * HEIGHT
+            )
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    html! {
+        <svg class="trend-chart" viewBox={format!("0 0 {} {}", WIDTH, HEIGHT)} preserveAspectRatio="none">
+            <polyline points={points} />
+        </svg>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        save::{test::saved_game, GameHistory, SaveState},
+        GameMode,
+    };
+
+    use super::Dashboard;
+
+    #[test]
+    fn test_dashboard() {
+        let game = |puzzle, words: &[&str], answer: &str, time: Option<i64>| SaveState {
+            started_at: time.map(|_| 1000),
+            finished_at: time.map(|t| 1000 + t),
+            ..saved_game(GameMode::Daily(puzzle), answer, words)
+        };
+        let mut history = GameHistory::default();
+        history
+            .scores
+            .insert(400, game(400, &["crane", "abbey"], "abbey", Some(90)));
+        history
+            .scores
+            .insert(401, game(401, &["crane", "stoic", "build"], "build", None));
+        history
+            .scores
+            .insert(402, game(402, &["slate", "crane"], "pauls", Some(30)));
+
+        let dashboard = Dashboard::from_history(&history);
+        assert_eq!(dashboard.average_guesses, Some(2.5));
+        assert_eq!(dashboard.results[&401], Some(3));
+        assert_eq!(dashboard.results[&402], None);
+        assert_eq!(
+            dashboard.openers,
+            [("crane".to_string(), 2), ("slate".to_string(), 1)]
+        );
+        assert_eq!(dashboard.letters[0], ('a', 5));
+        // only won games count towards the time
+        assert_eq!(dashboard.average_solve_time, Some(90));
+        assert_eq!(dashboard.trend, [2., 2.5, 8. / 3.]);
+    }
+}
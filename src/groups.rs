@@ -0,0 +1,186 @@
+//! The leaderboard of the group set up alongside syncing. Finishing the daily
+//! puzzle posts the result to the group, and the server answers with everyone
+//! else's, boards included now that it's been played.
+use paudle_core::groups::{DailyResult, Entry, Leaderboard, Summary};
+use wasm_bindgen_futures::spawn_local;
+use yew::prelude::*;
+
+use crate::{
+    dashboard::format_duration,
+    save::{Outcome, SaveState},
+    scoreboard::score_char,
+    settings::{ShareFormat, SyncSettings},
+    sync::{get_json, post_json, ServerError},
+};
+
+pub fn daily_result(game: &SaveState) -> DailyResult {
+    DailyResult {
+        rows: game.guesses.clone(),
+        solved: game.was_won(),
+        seconds: game
+            .finished_at
+            .zip(game.started_at)
+            .and_then(|(finished, started)| u64::try_from(finished - started).ok()),
+    }
+}
+
+/// Posts `result` for `puzzle` and returns the leaderboard that comes back. The
+/// server only takes the first result, so once one is in, from here or another
+/// device, the leaderboard is fetched instead.
+async fn post_result(
+    settings: &SyncSettings,
+    group: &str,
+    puzzle: u32,
+    result: &DailyResult,
+) -> Result<Leaderboard, String> {
+    let server = settings.url.trim_end_matches('/');
+    let group = js_sys::encode_uri_component(group);
+    let profile = js_sys::encode_uri_component(&settings.profile);
+    let url = format!("{}/groups/{}/results/{}/{}", server, group, puzzle, profile);
+    match post_json(&url, result, &settings.key).await {
+        Err(ServerError::Status(409)) => {
+            let url = format!(
+                "{}/groups/{}/leaderboards/{}?profile={}",
+                server, group, puzzle, profile
+            );
+            get_json(&url, &settings.key).await
+        }
+        posted => posted,
+    }
+    .map_err(|e| e.to_string())
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Period {
+    Today,
+    Week,
+    Month,
+}
+
+impl Period {
+    const ALL: [Period; 3] = [Period::Today, Period::Week, Period::Month];
+
+    fn label(self) -> &'static str {
+        match self {
+            Period::Today => "Today",
+            Period::Week => "This week",
+            Period::Month => "This month",
+        }
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct GroupLeaderboardProps {
+    /// Sync settings with a group in them.
+    pub settings: SyncSettings,
+    pub puzzle: u32,
+    pub result: DailyResult,
+    /// How boards are drawn, the same as when sharing.
+    pub format: ShareFormat,
+}
+
+#[function_component(GroupLeaderboard)]
+pub fn group_leaderboard(props: &GroupLeaderboardProps) -> Html {
+    let leaderboard = use_state(|| None::<Result<Leaderboard, String>>);
+    let period = use_state(|| Period::Today);
+    {
+        let leaderboard = leaderboard.clone();
+        let settings = props.settings.clone();
+        let (puzzle, result) = (props.puzzle, props.result.clone());
+        use_effect_with_deps(
+            move |_| {
+                if let Some(group) = settings.group.clone() {
+                    spawn_local(async move {
+                        let posted = post_result(&settings, &group, puzzle, &result).await;
+                        leaderboard.set(Some(posted));
+                    });
+                }
+                || ()
+            },
+            (),
+        );
+    }
+    let board = match &*leaderboard {
+        None => return html! { <div class="group-leaderboard">{"Loading the group..."}</div> },
+        Some(Err(e)) => {
+            return html! {
+                <div class="group-leaderboard">{format!("Couldn't load the group: {}", e)}</div>
+            }
+        }
+        Some(Ok(board)) => board,
+    };
+    let periods = Period::ALL.into_iter().map(|p| {
+        let select = {
+            let period = period.clone();
+            Callback::from(move |_: MouseEvent| period.set(p))
+        };
+        html! {
+            <span
+                class={classes!("scoreboard-mode", (p == *period).then_some("selected"))}
+                onclick={select}
+            >
+                {p.label()}
+            </span>
+        }
+    });
+    let rows = match *period {
+        Period::Today => board
+            .today
+            .iter()
+            .enumerate()
+            .map(|entry| today_row(entry, props.format))
+            .collect::<Html>(),
+        Period::Week => board.week.iter().enumerate().map(summary_row).collect(),
+        Period::Month => board.month.iter().enumerate().map(summary_row).collect(),
+    };
+    html! {
+        <div class="group-leaderboard">
+            <div class="scoreboard-header">{&board.name}</div>
+            <div class="scoreboard-modes">{ for periods }</div>
+            {rows}
+        </div>
+    }
+}
+
+fn today_row((idx, entry): (usize, &Entry), format: ShareFormat) -> Html {
+    let result = match entry.guesses {
+        Some(1) => "1 guess".to_string(),
+        Some(guesses) => format!("{} guesses", guesses),
+        None => "didn't solve it".to_string(),
+    };
+    let time = entry
+        .seconds
+        .and_then(|s| i64::try_from(s).ok())
+        .map_or_else(String::new, |s| format!(", {}", format_duration(s)));
+    html! {
+        <div class="group-entry">
+            <div>{format!("{}. {}: {}{}", idx + 1, entry.name, result, time)}</div>
+            if let Some(board) = &entry.board {
+                <pre class="group-board">
+                    {board
+                        .iter()
+                        .map(|row| row.iter().map(|c| score_char(*c, format)).collect::<String>())
+                        .collect::<Vec<_>>()
+                        .join("\n")}
+                </pre>
+            }
+        </div>
+    }
+}
+
+fn summary_row((idx, summary): (usize, &Summary)) -> Html {
+    let mut text = format!(
+        "{}. {}: solved {} of {}",
+        idx + 1,
+        summary.name,
+        summary.solved,
+        summary.played
+    );
+    if let Some(guesses) = summary.average_guesses {
+        text.push_str(&format!(", {:.1} guesses", guesses));
+    }
+    if let Some(secs) = summary.average_seconds {
+        text.push_str(&format!(", {}", format_duration(secs.round() as i64)));
+    }
+    html! { <div class="group-entry">{text}</div> }
+}
@@ -17,6 +17,17 @@ pub enum KeyStatus {
     Correct,
 }
 
+impl KeyStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Unused => "unused",
+            Self::Absent => "absent",
+            Self::Present => "present",
+            Self::Correct => "correct",
+        }
+    }
+}
+
 #[derive(PartialEq, Clone)]
 pub struct KeyValue {
     pub status: KeyStatus,
@@ -41,17 +52,16 @@ impl IntoPropValue<KeyType> for KeyValue {
 #[derive(Properties, PartialEq)]
 pub struct KeyProps {
     pub def: KeyType,
+    /// The key's status on each board in multi-board games, drawn as a grid
+    /// behind the letter.
+    #[prop_or_default]
+    pub quadrants: Vec<KeyStatus>,
 }
 
 impl KeyProps {
     fn status_string(&self) -> String {
         match &self.def {
-            KeyType::Letter(letter) => match letter.status {
-                KeyStatus::Unused => "unused",
-                KeyStatus::Absent => "absent",
-                KeyStatus::Present => "present",
-                KeyStatus::Correct => "correct",
-            },
+            KeyType::Letter(letter) => letter.status.as_str(),
             KeyType::Enter | KeyType::Backspace => "unused",
         }
         .to_string()
@@ -82,9 +92,19 @@ pub fn key(props: &KeyProps) -> Html {
         KeyType::Enter => "Enter".to_string(),
         KeyType::Backspace => "Backspace".to_string(),
     };
+    if props.quadrants.is_empty() {
+        return html! {
+          <div data-key-id={key_id} data-status={props.status_string()} class={props.class()}>
+            {props.disp()}
+          </div>
+        };
+    }
     html! {
-      <div data-key-id={key_id} data-status={props.status_string()} class={props.class()}>
-        {props.disp()}
+      <div data-key-id={key_id} data-status="unused" class={props.class()}>
+        <div class="key-quadrants" data-boards={props.quadrants.len().to_string()}>
+          { for props.quadrants.iter().map(|q| html! { <div data-status={q.as_str()} /> }) }
+        </div>
+        <span class="key-letter">{props.disp()}</span>
       </div>
     }
 }
@@ -11,6 +11,9 @@ pub struct Keyboard;
 #[derive(Properties, PartialEq)]
 pub struct KeyboardProperties {
     pub keys: KeyboardStatus,
+    /// One status per board in multi-board games. Takes precedence over `keys`.
+    #[prop_or_default]
+    pub boards: Vec<KeyboardStatus>,
     pub key_press: Callback<PaudleMsg>,
 }
 
@@ -25,7 +28,13 @@ impl Component for Keyboard {
 
     fn view(&self, ctx: &Context<Self>) -> Html {
         let key = |c: char| {
-            html! { <Key def={ctx.props().keys.get_status(c)} /> }
+            let quadrants = ctx
+                .props()
+                .boards
+                .iter()
+                .map(|b| b.get_status(c).status)
+                .collect::<Vec<_>>();
+            html! { <Key def={ctx.props().keys.get_status(c)} quadrants={quadrants} /> }
         };
 
         let row_one = ['Q', 'W', 'E', 'R', 'T', 'Y', 'U', 'I', 'O', 'P']
@@ -1,4 +1,4 @@
-use crate::CellValue;
+use paudle_core::CellValue;
 use std::collections::HashMap;
 
 use super::key::{KeyStatus, KeyValue};
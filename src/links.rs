@@ -0,0 +1,151 @@
+use paudle_core::{DEFAULT_WORD_LENGTH, WORD_LENGTHS};
+use web_sys::window;
+
+use crate::{settings::default_max_guesses, GUESS_LIMITS};
+
+/// A random game someone shared, as read from a link.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SeededLink {
+    pub seed: u64,
+    pub word_length: usize,
+    pub max_guesses: Option<usize>,
+}
+
+/// The short tag a seeded game goes by in share text, e.g. `r#8f3a`.
+pub fn seed_tag(seed: u64) -> String {
+    format!("r#{:x}", seed)
+}
+
+/// The `key=value` pairs in a query string like `?seed=8f3a&guesses=6`.
+pub fn query_pairs(search: &str) -> impl Iterator<Item = (&str, &str)> {
+    search
+        .trim_start_matches('?')
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+}
+
+pub fn guesses_param(max_guesses: Option<usize>) -> String {
+    max_guesses.map_or_else(|| "unlimited".to_string(), |max| max.to_string())
+}
+
+/// Reads a guess limit written by [`guesses_param`], falling back to the default
+/// for anything it doesn't recognise or that the game doesn't offer.
+pub fn parse_guesses_param(value: &str) -> Option<usize> {
+    match value {
+        "unlimited" => None,
+        value => value
+            .parse()
+            .ok()
+            .filter(|max| GUESS_LIMITS.contains(max))
+            .or_else(default_max_guesses),
+    }
+}
+
+/// The page's own address with `query` in place of whatever query it had.
+pub fn page_url(query: &str) -> String {
+    let location = window().expect("No window? Where am I?").location();
+    format!(
+        "{}{}?{}",
+        location.origin().unwrap_or_default(),
+        location.pathname().unwrap_or_default(),
+        query
+    )
+}
+
+pub fn seeded_query(link: &SeededLink) -> String {
+    format!(
+        "seed={:x}&length={}&guesses={}",
+        link.seed,
+        link.word_length,
+        guesses_param(link.max_guesses)
+    )
+}
+
+pub fn parse_seeded_query(search: &str) -> Option<SeededLink> {
+    let mut seed = None;
+    let mut word_length = None;
+    let mut max_guesses = default_max_guesses();
+    for (key, value) in query_pairs(search) {
+        match key {
+            "seed" => seed = u64::from_str_radix(value, 16).ok(),
+            "length" => {
+                word_length = value.parse().ok().filter(|len| WORD_LENGTHS.contains(len));
+            }
+            "guesses" => max_guesses = parse_guesses_param(value),
+            _ => {}
+        }
+    }
+    Some(SeededLink {
+        seed: seed?,
+        word_length: word_length.unwrap_or(DEFAULT_WORD_LENGTH),
+        max_guesses,
+    })
+}
+
+/// The page's query string, or an empty one if there's no window.
+pub fn current_query() -> String {
+    window()
+        .and_then(|w| w.location().search().ok())
+        .unwrap_or_default()
+}
+
+/// The seeded game in the page's query string, if it was opened from a link.
+pub fn seeded_from_url() -> Option<SeededLink> {
+    parse_seeded_query(&current_query())
+}
+
+/// Swaps the query in the address bar without reloading the page. An empty query
+/// leaves just the path.
+pub fn set_url_query(query: &str) {
+    if let Some(window) = window() {
+        let path = window.location().pathname().unwrap_or_default();
+        let url = if query.is_empty() {
+            path
+        } else {
+            format!("{}?{}", path, query)
+        };
+        if let Ok(history) = window.history() {
+            let _ = history.replace_state_with_url(&wasm_bindgen::JsValue::NULL, "", Some(&url));
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_guesses_param, parse_seeded_query, seed_tag, seeded_query, SeededLink};
+
+    #[test]
+    fn test_seeded_query() {
+        let link = SeededLink {
+            seed: 0x8f3a,
+            word_length: 6,
+            max_guesses: None,
+        };
+        assert_eq!(seed_tag(link.seed), "r#8f3a");
+        let query = seeded_query(&link);
+        assert_eq!(query, "seed=8f3a&length=6&guesses=unlimited");
+        assert_eq!(parse_seeded_query(&format!("?{}", query)), Some(link));
+        assert_eq!(
+            parse_seeded_query("?seed=8f3a").map(|l| (l.word_length, l.max_guesses)),
+            Some((5, Some(6)))
+        );
+        assert_eq!(parse_seeded_query("?puzzle=abcd"), None);
+    }
+
+    #[test]
+    fn test_out_of_range_links() {
+        assert_eq!(parse_guesses_param("1000"), Some(6));
+        assert_eq!(parse_guesses_param("0"), Some(6));
+        assert_eq!(parse_guesses_param("10"), Some(10));
+        assert_eq!(
+            parse_seeded_query("?seed=1&length=40&guesses=1000")
+                .map(|l| (l.word_length, l.max_guesses)),
+            Some((5, Some(6)))
+        );
+        assert_eq!(
+            parse_seeded_query("?seed=1&length=8&guesses=3")
+                .map(|l| (l.word_length, l.max_guesses)),
+            Some((8, Some(3)))
+        );
+    }
+}
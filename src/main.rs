@@ -1,58 +1,254 @@
 #![allow(clippy::module_name_repetitions)]
+mod absurdle;
+mod analysis;
 mod board;
+mod custom;
+mod dashboard;
+mod groups;
 mod keyboard;
+mod links;
+mod multi;
 mod save;
 mod scoreboard;
+mod settings;
+mod store;
+mod sync;
+mod transfer;
+mod versioned;
+mod versus;
 
 use gloo_events::EventListener;
+use gloo_timers::callback::Interval;
 use patternfly_yew::{Backdrop, BackdropDispatcher, Bullseye, Modal, ModalVariant};
 use patternfly_yew::{BackdropViewer, Toast, ToastDispatcher, ToastViewer, Type};
-use rand::SeedableRng;
-use rand::{prelude::IteratorRandom, thread_rng};
+use paudle_core::{
+    analyze_game, answer_list, daily_word, dictionary, puzzle_number, remaining_answers,
+    schedule::seeded_words, solve, CellValue, Game, GameState, GuessError, DEFAULT_MAX_GUESSES,
+    DEFAULT_WORD_LENGTH, MIN_GUESSES, MULTI_BOARD_COUNTS, WORD_LENGTHS,
+};
+use rand::{thread_rng, Rng};
 use save::update_saved_state;
-use save::{load_game_history, load_saved_sate};
+use save::{
+    has_saved_state, load_game_history, load_history, load_random_history, load_saved_sate,
+    SaveState,
+};
+use scoreboard::{generate_score_copy, share_formats, ModeStats, Stats};
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
-use std::{collections::HashMap, mem};
 use wasm_bindgen::JsCast;
-use web_sys::window;
+use web_sys::{console, window, HtmlInputElement, HtmlSelectElement};
 use yew::prelude::*;
 
-use board::{Board, CellValue};
+use absurdle::AbsurdlePaudle;
+use analysis::Analysis;
+use board::Board;
+use custom::{puzzle_from_url, puzzle_url, CreatePuzzle, CustomPuzzle};
+use dashboard::{Dashboard, StatsDashboard};
+use groups::{daily_result, GroupLeaderboard};
 use keyboard::{Keyboard, KeyboardStatus, BACKSPACE, ENTER, ESCAPE};
-use scoreboard::{Scoreboard, ScoreboardFooter};
+use links::{page_url, seed_tag, seeded_from_url, seeded_query, set_url_query, SeededLink};
+use multi::{multi_name, MultiPaudle};
+use scoreboard::{NextPuzzle, Scoreboard, ScoreboardFooter};
+use settings::{
+    load_settings, update_settings, GameType, PlayMode, Settings, ShareFormat, SyncSettings,
+};
+use store::{IndexedDbStore, LocalStore, MemoryStore, SaveStore, Store};
+use sync::SyncForm;
+use transfer::HistoryTransfer;
+use versus::VersusPaudle;
+
+/// The fixed guess limits offered in the header, on top of unlimited guesses.
+const GUESS_LIMITS: std::ops::RangeInclusive<usize> = MIN_GUESSES..=10;
+
+pub struct App {
+    game_type: GameType,
+    /// Games aren't shown until the store has been opened.
+    store: Option<Store>,
+}
+
+pub enum AppMsg {
+    SetGameType(GameType),
+    StoreOpened(Store),
+}
+
+/// Saves go to IndexedDB where there is one, then LocalStorage. Browsers that
+/// block both still get to play, but nothing outlives the page.
+async fn open_store() -> Store {
+    match IndexedDbStore::open().await {
+        Ok(store) => Store::new(store),
+        Err(e) => {
+            console::log_1(&format!("Couldn't open IndexedDB: {}", e).into());
+            if window()
+                .and_then(|w| w.local_storage().ok().flatten())
+                .is_some()
+            {
+                Store::new(LocalStore)
+            } else {
+                Store::new(MemoryStore::default())
+            }
+        }
+    }
+}
+
+impl Component for App {
+    type Message = AppMsg;
+
+    type Properties = ();
+
+    fn create(ctx: &Context<Self>) -> Self {
+        ctx.link()
+            .send_future(async { AppMsg::StoreOpened(open_store().await) });
+        // Puzzle and seed links are always classic games
+        let game_type = if puzzle_from_url().is_some() || seeded_from_url().is_some() {
+            GameType::Classic
+        } else {
+            load_settings().game_type
+        };
+        Self {
+            game_type,
+            store: None,
+        }
+    }
+
+    fn update(&mut self, _ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            AppMsg::SetGameType(game_type) => {
+                let settings = Settings {
+                    game_type,
+                    ..load_settings()
+                };
+                update_settings(&settings);
+                set_url_query("");
+                BackdropDispatcher::default().close();
+                self.game_type = game_type;
+                true
+            }
+            AppMsg::StoreOpened(store) => {
+                self.store = Some(store);
+                true
+            }
+        }
+    }
+
+    #[allow(clippy::let_unit_value, clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let set_game_type = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            match select.value().as_str() {
+                "classic" => Some(AppMsg::SetGameType(GameType::Classic)),
+                "absurdle" => Some(AppMsg::SetGameType(GameType::Absurdle)),
+                "versus" => Some(AppMsg::SetGameType(GameType::Versus)),
+                value => value
+                    .parse()
+                    .ok()
+                    .map(|boards| AppMsg::SetGameType(GameType::Multi(boards))),
+            }
+        });
+        let game_type = self.game_type;
+
+        html! {
+            <div class="page">
+                <div class="header">
+                    <div class="title">{"Paudle"}</div>
+                    <select class="game-type" onchange={set_game_type}>
+                        <option value="classic" selected={game_type == GameType::Classic}>
+                            {"Classic"}
+                        </option>
+                        { for MULTI_BOARD_COUNTS.into_iter().map(|boards| html! {
+                            <option value={boards.to_string()} selected={game_type == GameType::Multi(boards)}>
+                                {format!("{} ({} boards)", multi_name(boards), boards)}
+                            </option>
+                        }) }
+                        <option value="absurdle" selected={game_type == GameType::Absurdle}>
+                            {"Absurdle"}
+                        </option>
+                        <option value="versus" selected={game_type == GameType::Versus}>
+                            {"Versus"}
+                        </option>
+                    </select>
+                </div>
+                {
+                    match (game_type, self.store.clone()) {
+                        (_, None) => html! {},
+                        (GameType::Classic, Some(store)) => html! { <Paudle store={store} /> },
+                        (GameType::Multi(boards), Some(store)) => html! {
+                            <MultiPaudle boards={boards} store={store} />
+                        },
+                        (GameType::Absurdle, Some(store)) => html! { <AbsurdlePaudle store={store} /> },
+                        (GameType::Versus, Some(_)) => html! { <VersusPaudle /> },
+                    }
+                }
+                <BackdropViewer />
+                <ToastViewer />
+            </div>
+        }
+    }
+}
 
-const WORD_LIST: &str = include_str!("awords.txt");
 pub struct Paudle {
-    word: String,
-    guesses: Vec<Vec<CellValue>>,
+    game: Game,
     keyboard_status: KeyboardStatus,
-    current_guess: String,
-    word_length: usize,
-    max_guesses: usize,
-    game_state: GameState,
     game_mode: GameMode,
+    settings: Settings,
+    hints_used: bool,
+    started_at: Option<i64>,
+    finished_at: Option<i64>,
+    store: Store,
+    /// Today's puzzle, once it has come out while an earlier one is still being
+    /// played.
+    new_puzzle: Option<u32>,
+    keydown_listener: Option<EventListener>,
+    storage_listener: Option<EventListener>,
+    rollover_timer: Option<Interval>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct PaudleProps {
+    pub store: Store,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum GameMode {
-    Daily(i64),
-    Random,
+    /// The daily puzzle with this number.
+    Daily(u32),
+    /// A random game that anyone with the seed can replay.
+    Seeded(u64),
+    /// A puzzle from a link, identified by its encoded answer.
+    Custom(String),
 }
 
-fn get_todays_key() -> i64 {
-    chrono::Local::now().date().and_hms(0, 0, 0).timestamp()
+impl GameMode {
+    pub fn play_mode(&self) -> PlayMode {
+        match self {
+            GameMode::Daily(_) => PlayMode::Daily,
+            GameMode::Seeded(_) => PlayMode::Random,
+            GameMode::Custom(_) => PlayMode::Custom,
+        }
+    }
 }
 
-impl Default for Paudle {
-    fn default() -> Self {
-        let word_choices = WORD_LIST.lines();
-        let ts = get_todays_key();
-        #[allow(clippy::cast_sign_loss)]
-        let mut rng = rand::prelude::StdRng::seed_from_u64(ts as u64);
-        let word = word_choices.choose(&mut rng).unwrap().to_string();
-        Self::with_word(word, GameMode::Daily(ts))
-    }
+/// Today's puzzle number, going by the player's local date.
+fn todays_puzzle() -> u32 {
+    puzzle_number(chrono::Local::now().naive_local().date()).unwrap_or(0)
+}
+
+/// How long until the next puzzle comes out at local midnight. Worked out between
+/// real instants, so a clock change tonight is counted.
+fn time_to_next_puzzle() -> chrono::Duration {
+    let now = chrono::Local::now();
+    let tomorrow = now.date_naive() + chrono::Duration::days(1);
+    // Where a clock change skips midnight, the day starts at the first time it has
+    let next = (0..24).find_map(|hour| {
+        let start = tomorrow.and_hms_opt(hour, 0, 0)?;
+        chrono::TimeZone::from_local_datetime(&chrono::Local, &start).earliest()
+    });
+    next.map_or_else(chrono::Duration::zero, |next| next - now)
+}
+
+/// Seeds for new random games. Kept small so the tag in the share text stays short;
+/// links can still carry any seed.
+fn new_seed() -> u64 {
+    thread_rng().gen_range(0..0x10000)
 }
 
 pub enum PaudleMsg {
@@ -60,60 +256,472 @@ pub enum PaudleMsg {
     Backspace,
     Submit,
     StartRandom,
+    ToggleHardMode,
+    ToggleDailyHints,
+    Hint,
+    CreatePuzzle,
+    ShowStats,
+    ShowSync,
+    SetSync(Option<SyncSettings>),
+    SetShareFormat(ShareFormat),
+    SetWordLength(usize),
+    SetMaxGuesses(Option<usize>),
+    /// Another tab saved something.
+    StorageChanged,
+    /// Checks whether the day has rolled over.
+    Tick,
+    PlayNewPuzzle,
+    SetPlayMode(PlayMode),
     Escape,
-}
-
-#[derive(PartialEq, Clone)]
-pub enum GameState {
-    InProgress,
-    Won,
-    Lost,
+    JoinVersus,
+    VersusMessage(paudle_core::versus::ServerMessage),
+    /// The connection to the versus server went away.
+    VersusClosed,
+    LeaveVersus,
+    Rematch,
 }
 
 impl Paudle {
-    fn with_word(word: String, game_mode: GameMode) -> Self {
+    fn with_word(word: String, game_mode: GameMode, settings: Settings, store: Store) -> Self {
+        let game = Game::new(word).with_max_guesses(settings.max_guesses);
+        Self::with_game(game, game_mode, settings, store)
+    }
+
+    fn with_game(mut game: Game, game_mode: GameMode, settings: Settings, store: Store) -> Self {
+        game.set_hard_mode(settings.hard_mode);
         Self {
-            word,
-            guesses: Vec::new(),
+            game,
             keyboard_status: KeyboardStatus::default(),
-            current_guess: String::new(),
-            word_length: 5,
-            max_guesses: 6,
-            game_state: GameState::InProgress,
             game_mode,
+            settings,
+            hints_used: false,
+            started_at: None,
+            finished_at: None,
+            store,
+            new_puzzle: None,
+            keydown_listener: None,
+            storage_listener: None,
+            rollover_timer: None,
         }
     }
 
-    fn random() -> Self {
-        let word_choices = WORD_LIST.lines();
-        let mut rng = thread_rng();
-        let word = word_choices.choose(&mut rng).unwrap().to_string();
-        Self::with_word(word, GameMode::Random)
+    /// Swaps in a new game, keeping the page's listeners attached.
+    fn replace_game(&mut self, mut new_game: Paudle) {
+        new_game.keydown_listener = self.keydown_listener.take();
+        new_game.storage_listener = self.storage_listener.take();
+        new_game.rollover_timer = self.rollover_timer.take();
+        *self = new_game;
+        self.sync_url();
+        self.remember_play_mode();
     }
 
-    fn eval_and_add_guess(&mut self, guess: &str) {
-        let new_guess = evaluate_guess(&self.word, &guess.to_lowercase());
-        self.add_guess(new_guess);
+    /// The game to play in `play_mode`: the one left unfinished there, or a new
+    /// one. Custom puzzles only come from links, so there may not be one.
+    fn resume(play_mode: PlayMode, settings: Settings, store: Store) -> Option<Self> {
+        match load_saved_sate(&store, play_mode, settings.word_length) {
+            Some(saved_state) => Some(Paudle::from_saved(saved_state, store)),
+            None => match play_mode {
+                PlayMode::Daily => Some(Paudle::todays_game(settings, store)),
+                PlayMode::Random => Some(Paudle::random(settings, store)),
+                PlayMode::Custom => None,
+            },
+        }
+    }
+
+    /// Switches to another kind of game, leaving this one saved where it is.
+    fn switch_play_mode(&mut self, ctx: &Context<Self>, play_mode: PlayMode) -> bool {
+        match Paudle::resume(play_mode, self.settings.clone(), self.store.clone()) {
+            Some(new_game) => {
+                self.replace_game(new_game);
+                if self.game.is_over() {
+                    self.show_scoreboard(ctx);
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remembers which kind of game is being played, so the next visit starts there.
+    fn remember_play_mode(&mut self) {
+        let play_mode = self.game_mode.play_mode();
+        if self.settings.play_mode != play_mode {
+            self.settings.play_mode = play_mode;
+            update_settings(&self.settings);
+        }
+    }
+
+    fn daily(settings: Settings, store: Store) -> Self {
+        let word = daily_word(settings.word_length, todays_puzzle())
+            .unwrap()
+            .to_string();
+        Self::with_word(word, GameMode::Daily(todays_puzzle()), settings, store)
+    }
+
+    fn todays_game(settings: Settings, store: Store) -> Self {
+        match load_game_history(&store, settings.word_length, settings.max_guesses)
+            .scores
+            .remove(&todays_puzzle())
+        {
+            Some(saved_state) => Paudle::from_saved(saved_state, store),
+            None => Paudle::daily(settings, store),
+        }
+    }
+
+    fn random(settings: Settings, store: Store) -> Self {
+        let link = SeededLink {
+            seed: new_seed(),
+            word_length: settings.word_length,
+            max_guesses: settings.max_guesses,
+        };
+        Self::seeded(link, settings, store)
+    }
+
+    /// The game for `link`. The link's word length and guess limit win over the
+    /// player's usual ones so everyone gets the same game.
+    fn seeded(link: SeededLink, settings: Settings, store: Store) -> Self {
+        let word = seeded_words(link.word_length, link.seed, 1)
+            .or_else(|| seeded_words(DEFAULT_WORD_LENGTH, link.seed, 1))
+            .unwrap()[0]
+            .to_string();
+        Self::with_game(
+            Game::new(word).with_max_guesses(link.max_guesses),
+            GameMode::Seeded(link.seed),
+            settings,
+            store,
+        )
+    }
+
+    /// Picks up a shared seeded game where this player left it, if they've played it.
+    fn load_seeded(link: SeededLink, saved_state: Option<SaveState>, store: Store) -> Self {
+        let game_mode = GameMode::Seeded(link.seed);
+        let saved_state = saved_state
+            .filter(|s| s.game_mode == game_mode)
+            .or_else(|| {
+                load_random_history(&store, link.word_length, link.max_guesses)
+                    .scores
+                    .into_values()
+                    .find(|s| s.game_mode == game_mode)
+            });
+        match saved_state {
+            Some(saved_state) => Paudle::from_saved(saved_state, store),
+            None => Paudle::seeded(link, load_settings(), store),
+        }
+    }
+
+    fn custom(puzzle: CustomPuzzle, settings: Settings, store: Store) -> Self {
+        // The puzzle's guess limit wins over the player's usual one
+        let game = Game::new(puzzle.word).with_max_guesses(puzzle.max_guesses);
+        Self::with_game(game, GameMode::Custom(puzzle.code), settings, store)
+    }
+
+    /// Keeps the address bar pointing at the game being played: seeded games can be
+    /// shared straight from it. Puzzle links are dropped once loaded so reloading
+    /// picks up the saved game instead of starting over.
+    fn sync_url(&self) {
+        match self.game_mode {
+            GameMode::Seeded(seed) => set_url_query(&seeded_query(&SeededLink {
+                seed,
+                word_length: self.game.word_length(),
+                max_guesses: self.game.max_guesses(),
+            })),
+            GameMode::Daily(_) | GameMode::Custom(_) => set_url_query(""),
+        }
+    }
+
+    /// Notices when a new daily puzzle comes out while the page is open. A finished
+    /// game makes way for it straight away; one still being played gets to offer it.
+    fn check_for_new_puzzle(&mut self, ctx: &Context<Self>) -> bool {
+        let today = todays_puzzle();
+        match self.game_mode {
+            GameMode::Daily(puzzle) if puzzle != today && self.new_puzzle != Some(today) => {}
+            _ => return false,
+        }
+        toast_info(format!("Paudle #{} is out!", today));
+        if self.game.is_over() {
+            BackdropDispatcher::default().close();
+            self.play_new_puzzle(ctx);
+        } else {
+            self.new_puzzle = Some(today);
+        }
+        true
+    }
+
+    fn play_new_puzzle(&mut self, ctx: &Context<Self>) {
+        self.replace_game(Paudle::todays_game(
+            self.settings.clone(),
+            self.store.clone(),
+        ));
+        update_saved_state(&self.store, self);
+        if self.game.is_over() {
+            self.show_scoreboard(ctx);
+        }
+    }
+
+    /// Picks up what another tab did to the game being played. A daily game finished
+    /// there is finished here too, and the same game further along there replaces
+    /// this one.
+    fn merge_saved(&mut self, ctx: &Context<Self>) -> bool {
+        if let (GameMode::Daily(puzzle), false) = (&self.game_mode, self.game.is_over()) {
+            let finished = load_game_history(
+                &self.store,
+                self.game.word_length(),
+                self.game.max_guesses(),
+            )
+            .scores
+            .remove(puzzle);
+            if let Some(finished) = finished {
+                self.replace_game(Paudle::from_saved(finished, self.store.clone()));
+                self.show_scoreboard(ctx);
+                return true;
+            }
+        }
+        match load_saved_sate(
+            &self.store,
+            self.game_mode.play_mode(),
+            self.game.word_length(),
+        ) {
+            Some(saved_state)
+                if saved_state.game_mode == self.game_mode
+                    && saved_state.guesses.len() > self.game.guesses().len() =>
+            {
+                self.replace_game(Paudle::from_saved(saved_state, self.store.clone()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Switches to a game with a different word length or guess limit. Each word
+    /// length keeps its own save, but a guess limit can only be changed before the
+    /// first guess or after the game is over.
+    fn change_game_settings(&mut self, ctx: &Context<Self>, settings: Settings) -> bool {
+        let limit_changed = settings.max_guesses != self.game.max_guesses();
+        if limit_changed && !self.game.is_over() && !self.game.guesses().is_empty() {
+            toast_error("Finish the current game before changing this setting".into());
+            return true;
+        }
+        update_settings(&settings);
+        let store = self.store.clone();
+        let new_game = match self.game_mode.play_mode() {
+            PlayMode::Daily => Paudle::resume(PlayMode::Daily, settings, store),
+            // Custom puzzles have a fixed word, so carry on with random games
+            PlayMode::Random | PlayMode::Custom => {
+                Paudle::resume(PlayMode::Random, settings, store)
+            }
+        };
+        self.replace_game(new_game.expect("daily and random games always resume"));
+        update_saved_state(&self.store, self);
+        if self.game.is_over() {
+            self.show_scoreboard(ctx);
+        }
+        true
+    }
+
+    /// Number of rows to draw: the guess limit, or enough to fit every guess plus
+    /// the one being typed when there's no limit.
+    fn row_count(&self) -> usize {
+        self.game.max_guesses().unwrap_or_else(|| {
+            let rows = self.game.guesses().len() + usize::from(!self.game.is_over());
+            rows.max(DEFAULT_MAX_GUESSES)
+        })
+    }
+
+    /// Hints spoil the daily word for everyone comparing scores, so they're off
+    /// there unless the player has opted in.
+    fn hints_allowed(&self) -> bool {
+        !matches!(self.game_mode, GameMode::Daily(_)) || self.settings.daily_hints
+    }
+
+    /// Words the answer could be. Custom puzzles can use anything in the dictionary.
+    fn candidate_words(&self) -> std::str::Lines<'static> {
+        let word_length = self.game.word_length();
+        match self.game_mode {
+            GameMode::Custom(_) => dictionary(word_length),
+            _ => answer_list(word_length),
+        }
+        .unwrap()
+        .lines()
+    }
+
+    fn show_hint(&mut self) -> bool {
+        if !self.hints_allowed() {
+            return false;
+        }
+        match solve(self.candidate_words(), self.game.guesses()).first() {
+            Some(best) => {
+                toast_info(format!(r#"Try "{}""#, best.word));
+                self.hints_used = true;
+                update_saved_state(&self.store, self);
+            }
+            None => toast_error("No words left that fit those clues".into()),
+        }
+        true
     }
 
     fn add_guess(&mut self, new_guess: Vec<CellValue>) {
         self.keyboard_status.update_status(&new_guess);
-        let correct = new_guess.iter().all(|g| matches!(g, CellValue::Correct(_)));
-        self.guesses.push(new_guess);
-        if correct {
-            self.game_state = GameState::Won;
-        } else if self.guesses.len() == self.max_guesses {
-            self.game_state = GameState::Lost;
+        self.game.add_guess(new_guess);
+    }
+
+    /// Stats for every game of `play_mode` played with this word length and guess
+    /// limit.
+    fn mode_stats(&self, play_mode: PlayMode) -> ModeStats {
+        let word_length = self.game.word_length();
+        let history = load_history(&self.store, play_mode, word_length, self.game.max_guesses());
+        let bar_count = self.game.max_guesses().unwrap_or_else(|| {
+            history
+                .scores
+                .values()
+                .map(|val| val.guesses.len())
+                .chain([self.game.guesses().len(), DEFAULT_MAX_GUESSES])
+                .max()
+                .unwrap_or(DEFAULT_MAX_GUESSES)
+        });
+        let (label, caption) = match play_mode {
+            PlayMode::Daily => ("Daily", format!("{} letters", word_length)),
+            PlayMode::Random => ("Random", format!("Random, {} letters", word_length)),
+            PlayMode::Custom => ("Custom", format!("Custom, {} letters", word_length)),
+        };
+        let highlight = (play_mode == self.game_mode.play_mode()
+            && self.game.state() == GameState::Won)
+            .then(|| self.game.guesses().len());
+        ModeStats {
+            label: label.to_string(),
+            caption,
+            stats: Stats::from_history(&history, 1..=bar_count),
+            highlight,
         }
     }
 
+    /// Syncs daily histories with the server, if one is set up, and then picks up
+    /// whatever came back as if another tab had saved it.
+    fn sync_histories(&self, ctx: &Context<Self>) {
+        let Some(settings) = self.settings.sync.clone() else {
+            return;
+        };
+        let store = self.store.clone();
+        ctx.link().send_future_batch(async move {
+            match sync::sync(&store, &settings).await {
+                Ok(true) => vec![PaudleMsg::StorageChanged],
+                Ok(false) => vec![],
+                Err(e) => {
+                    console::log_1(&format!("Couldn't sync: {}", e).into());
+                    vec![]
+                }
+            }
+        });
+    }
+
+    /// Opens the stats page for the kind of game being played.
+    #[allow(clippy::unnecessary_operation)]
+    fn show_stats(&self) {
+        let play_mode = self.game_mode.play_mode();
+        let history = load_history(
+            &self.store,
+            play_mode,
+            self.game.word_length(),
+            self.game.max_guesses(),
+        );
+        let title = format!("{} Stats", self.mode_stats(play_mode).caption);
+        BackdropDispatcher::default().open(Backdrop {
+            content: html! {
+                <Bullseye>
+                    <Modal
+                        title={title}
+                        variant={ModalVariant::Small}
+                        footer={Some(html! {<HistoryTransfer store={self.store.clone()} />})}
+                    >
+                        <StatsDashboard
+                            dashboard={Dashboard::from_history(&history)}
+                            today={todays_puzzle()}
+                        />
+                    </Modal>
+                </Bullseye>
+            },
+        });
+    }
+
+    /// The group's leaderboard for today, if there's a group to post to. Groups
+    /// only compare the standard five letter, six guess puzzle.
+    fn group_leaderboard(&self) -> Html {
+        let settings = match &self.settings.sync {
+            Some(settings) if settings.group.is_some() => settings.clone(),
+            _ => return html! {},
+        };
+        match self.game_mode {
+            GameMode::Daily(puzzle)
+                if self.game.word_length() == DEFAULT_WORD_LENGTH
+                    && self.game.max_guesses() == Some(DEFAULT_MAX_GUESSES) =>
+            {
+                html! {
+                    <GroupLeaderboard
+                        settings={settings}
+                        puzzle={puzzle}
+                        result={daily_result(&SaveState::from_live(self))}
+                        format={self.settings.share_format}
+                    />
+                }
+            }
+            _ => html! {},
+        }
+    }
+
+    #[allow(clippy::let_unit_value, clippy::unnecessary_operation)]
     fn show_scoreboard(&mut self, ctx: &Context<Self>) {
         let clear = ctx.link().callback(|msg: PaudleMsg| msg);
-        let title = if self.game_state == GameState::Won {
+        let title = if self.game.state() == GameState::Won {
             "Winner!".to_string()
         } else {
-            format!(r#"Game Over. Word was "{}""#, self.word)
+            format!(r#"Game Over. Word was "{}""#, self.game.word())
         };
+        let mut modifiers = String::new();
+        if self.game.hard_mode() {
+            modifiers.push('*');
+        }
+        if self.hints_used {
+            modifiers.push('h');
+        }
+        let name = match self.game_mode {
+            GameMode::Daily(puzzle) => format!("Paudle #{}", puzzle),
+            GameMode::Seeded(seed) => format!("Paudle {}", seed_tag(seed)),
+            GameMode::Custom(_) => "Paudle Custom".to_string(),
+        };
+        let link = match &self.game_mode {
+            GameMode::Daily(_) => None,
+            GameMode::Seeded(seed) => Some(page_url(&seeded_query(&SeededLink {
+                seed: *seed,
+                word_length: self.game.word_length(),
+                max_guesses: self.game.max_guesses(),
+            }))),
+            GameMode::Custom(code) => Some(puzzle_url(code, self.game.max_guesses())),
+        };
+        let share_texts = share_formats(|format| {
+            let mut share_text = generate_score_copy(
+                &name,
+                self.game.state() == GameState::Won,
+                self.game.max_guesses(),
+                self.game.guesses(),
+                &modifiers,
+                format,
+            );
+            if let Some(link) = &link {
+                share_text.push_str("\n\n");
+                share_text.push_str(link);
+            }
+            share_text
+        });
+        let play_modes = [PlayMode::Daily, PlayMode::Random, PlayMode::Custom];
+        let modes = play_modes
+            .into_iter()
+            .map(|play_mode| self.mode_stats(play_mode))
+            .collect::<Vec<_>>();
+        let selected = play_modes
+            .iter()
+            .position(|m| *m == self.game_mode.play_mode())
+            .unwrap_or(0);
+        let analysis = analyze_game(self.candidate_words(), self.game.guesses());
+        let group = self.group_leaderboard();
         let bd = Backdrop {
             content: html! {
                 <Bullseye>
@@ -121,14 +729,20 @@ impl Paudle {
                         title={title}
                         variant={ModalVariant::Small}
                         footer={Some(html!{<ScoreboardFooter
-                                                guesses={self.guesses.clone()}
-                                                won={self.game_state == GameState::Won}
-                                                max_guesses={self.max_guesses}
-                                                random={self.game_mode == GameMode::Random}
+                                                share_texts={share_texts}
+                                                format={self.settings.share_format}
                                                 clear={clear}
                                             />})}
                     >
-                        <Scoreboard />
+                        <Scoreboard
+                            modes={modes}
+                            selected={selected}
+                        />
+                        if matches!(self.game_mode, GameMode::Daily(_)) {
+                            <NextPuzzle />
+                        }
+                        {group}
+                        <Analysis rows={analysis} />
                     </Modal>
                 </Bullseye>
             },
@@ -140,57 +754,144 @@ impl Paudle {
 impl Component for Paudle {
     type Message = PaudleMsg;
 
-    type Properties = ();
+    type Properties = PaudleProps;
 
-    fn create(_ctx: &Context<Self>) -> Self {
-        let saved_state = load_saved_sate();
-        if let Some(saved_state) = saved_state {
-            saved_state.into()
-        } else {
-            let history = load_game_history();
-            history
-                .scores
-                .get(&get_todays_key())
-                .cloned()
-                .map_or_else(Paudle::default, Into::into)
+    fn create(ctx: &Context<Self>) -> Self {
+        let store = ctx.props().store.clone();
+        if let Some(link) = seeded_from_url() {
+            let saved_state = load_saved_sate(&store, PlayMode::Random, link.word_length);
+            return Paudle::load_seeded(link, saved_state, store);
+        }
+        if let Some(puzzle) = puzzle_from_url() {
+            let game_mode = GameMode::Custom(puzzle.code.clone());
+            return match load_saved_sate(&store, PlayMode::Custom, puzzle.word.len()) {
+                Some(saved_state) if saved_state.game_mode == game_mode => {
+                    Paudle::from_saved(saved_state, store)
+                }
+                _ => Paudle::custom(puzzle, load_settings(), store),
+            };
         }
+        let settings = load_settings();
+        Paudle::resume(settings.play_mode, settings.clone(), store.clone())
+            .unwrap_or_else(|| Paudle::todays_game(settings, store))
     }
 
+    #[allow(clippy::let_unit_value, clippy::unnecessary_operation)]
     fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
-        match (self.game_state == GameState::InProgress, msg) {
-            (true, PaudleMsg::TypeLetter(c)) if self.current_guess.len() < self.word_length => {
-                self.current_guess.push(c.to_ascii_lowercase());
-                true
-            }
-            (true, PaudleMsg::Backspace) => {
-                self.current_guess.pop();
-                true
+        match (self.game.is_over(), msg) {
+            (false, PaudleMsg::TypeLetter(c)) => {
+                self.started_at
+                    .get_or_insert_with(|| chrono::Utc::now().timestamp());
+                self.game.type_letter(c)
             }
-            (true, PaudleMsg::Submit) => {
-                if self.current_guess.len() == self.word_length {
-                    if !WORD_LIST.contains(&self.current_guess) {
-                        ToastDispatcher::new().toast(Toast {
-                            title: "Word not in word list".into(),
-                            r#type: Type::Danger,
-                            timeout: Some(Duration::from_secs(2)),
-                            ..Toast::default()
-                        });
-                        return true;
+            (false, PaudleMsg::Backspace) => self.game.backspace(),
+            (false, PaudleMsg::Submit) => match self.game.submit() {
+                Ok(evaluation) => {
+                    self.keyboard_status.update_status(&evaluation);
+                    if self.game.is_over() {
+                        self.finished_at = Some(chrono::Utc::now().timestamp());
                     }
-                    let current_guess = mem::take(&mut self.current_guess);
-                    self.eval_and_add_guess(&current_guess);
-                    update_saved_state(self);
-                    if self.game_state != GameState::InProgress {
+                    update_saved_state(&self.store, self);
+                    if self.game.is_over() {
                         self.show_scoreboard(ctx);
+                        self.sync_histories(ctx);
                     }
                     true
-                } else {
-                    false
                 }
+                Err(e @ (GuessError::NotInWordList | GuessError::HardMode(_))) => {
+                    toast_error(e.to_string());
+                    true
+                }
+                Err(_) => false,
+            },
+            (false, PaudleMsg::Hint) => self.show_hint(),
+            (true, PaudleMsg::StartRandom) => self.switch_play_mode(ctx, PlayMode::Random),
+            (_, PaudleMsg::ToggleHardMode) => {
+                let hard_mode = !self.settings.hard_mode;
+                if !self.game.is_over() && !self.game.set_hard_mode(hard_mode) {
+                    toast_error("Hard mode can only be changed at the start of a game".into());
+                    return true;
+                }
+                self.settings.hard_mode = hard_mode;
+                update_settings(&self.settings);
+                if !self.game.is_over() {
+                    update_saved_state(&self.store, self);
+                }
+                true
+            }
+            (_, PaudleMsg::ToggleDailyHints) => {
+                self.settings.daily_hints = !self.settings.daily_hints;
+                update_settings(&self.settings);
+                true
+            }
+            (_, PaudleMsg::SetWordLength(word_length)) => {
+                if word_length == self.game.word_length() {
+                    return false;
+                }
+                let settings = Settings {
+                    word_length,
+                    ..self.settings.clone()
+                };
+                self.change_game_settings(ctx, settings)
+            }
+            (_, PaudleMsg::SetMaxGuesses(max_guesses)) => {
+                if max_guesses == self.game.max_guesses() {
+                    return false;
+                }
+                let settings = Settings {
+                    max_guesses,
+                    ..self.settings.clone()
+                };
+                self.change_game_settings(ctx, settings)
+            }
+            (_, PaudleMsg::CreatePuzzle) => {
+                BackdropDispatcher::default().open(Backdrop {
+                    content: html! {
+                        <Bullseye>
+                            <Modal title={"Create a puzzle".to_string()} variant={ModalVariant::Small}>
+                                <CreatePuzzle />
+                            </Modal>
+                        </Bullseye>
+                    },
+                });
+                false
             }
-            (false, PaudleMsg::StartRandom) => {
-                let mut new_game = Paudle::random();
-                mem::swap(self, &mut new_game);
+            (_, PaudleMsg::ShowStats) => {
+                self.show_stats();
+                false
+            }
+            (_, PaudleMsg::ShowSync) => {
+                let on_change = ctx.link().callback(PaudleMsg::SetSync);
+                BackdropDispatcher::default().open(Backdrop {
+                    content: html! {
+                        <Bullseye>
+                            <Modal title={"Sync with a server".to_string()} variant={ModalVariant::Small}>
+                                <SyncForm settings={self.settings.sync.clone()} on_change={on_change} />
+                            </Modal>
+                        </Bullseye>
+                    },
+                });
+                false
+            }
+            (_, PaudleMsg::SetSync(sync)) => {
+                BackdropDispatcher::default().close();
+                self.settings.sync = sync;
+                update_settings(&self.settings);
+                self.sync_histories(ctx);
+                true
+            }
+            (_, PaudleMsg::SetShareFormat(format)) => {
+                self.settings.share_format = format;
+                update_settings(&self.settings);
+                false
+            }
+            (_, PaudleMsg::StorageChanged) => self.merge_saved(ctx),
+            (_, PaudleMsg::Tick) => self.check_for_new_puzzle(ctx),
+            (_, PaudleMsg::SetPlayMode(play_mode)) => {
+                play_mode != self.game_mode.play_mode() && self.switch_play_mode(ctx, play_mode)
+            }
+            (_, PaudleMsg::PlayNewPuzzle) => {
+                self.play_new_puzzle(ctx);
                 true
             }
             (_, PaudleMsg::Escape) => {
@@ -201,21 +902,124 @@ impl Component for Paudle {
         }
     }
 
+    #[allow(clippy::unnecessary_operation)]
     fn view(&self, ctx: &Context<Self>) -> Html {
         let cb = ctx.link().callback(|msg: PaudleMsg| msg);
+        let create_puzzle = ctx.link().callback(|_: MouseEvent| PaudleMsg::CreatePuzzle);
+        let show_stats = ctx.link().callback(|_: MouseEvent| PaudleMsg::ShowStats);
+        let show_sync = ctx.link().callback(|_: MouseEvent| PaudleMsg::ShowSync);
+        let play_new_puzzle = ctx
+            .link()
+            .callback(|_: MouseEvent| PaudleMsg::PlayNewPuzzle);
+        let set_play_mode = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            match select.value().as_str() {
+                "daily" => Some(PaudleMsg::SetPlayMode(PlayMode::Daily)),
+                "random" => Some(PaudleMsg::SetPlayMode(PlayMode::Random)),
+                "custom" => Some(PaudleMsg::SetPlayMode(PlayMode::Custom)),
+                _ => None,
+            }
+        });
+        let play_mode = self.game_mode.play_mode();
+        let has_custom = play_mode == PlayMode::Custom
+            || has_saved_state(&self.store, PlayMode::Custom, self.game.word_length());
+
+        let toggle_hard_mode = ctx
+            .link()
+            .callback(|_: MouseEvent| PaudleMsg::ToggleHardMode);
+        let set_word_length = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            select.value().parse().ok().map(PaudleMsg::SetWordLength)
+        });
+        let word_length = self.game.word_length();
+        let set_max_guesses = ctx.link().batch_callback(|e: Event| {
+            let select = e.target_unchecked_into::<HtmlSelectElement>();
+            match select.value().as_str() {
+                "unlimited" => Some(PaudleMsg::SetMaxGuesses(None)),
+                value => value
+                    .parse()
+                    .ok()
+                    .map(|max| PaudleMsg::SetMaxGuesses(Some(max))),
+            }
+        });
+        let max_guesses = self.game.max_guesses();
+        let toggle_daily_hints = ctx
+            .link()
+            .callback(|_: MouseEvent| PaudleMsg::ToggleDailyHints);
+        let hints = if self.hints_allowed() && !self.game.is_over() {
+            let remaining = remaining_answers(self.candidate_words(), self.game.guesses()).len();
+            let hint = ctx.link().callback(|_: MouseEvent| PaudleMsg::Hint);
+            html! {
+                <div class="hints">
+                    <span class="words-remaining">
+                        {if remaining == 1 {
+                            "1 word remaining".to_string()
+                        } else {
+                            format!("{} words remaining", remaining)
+                        }}
+                    </span>
+                    <span class="hint-button" onclick={hint}>{"Hint"}</span>
+                </div>
+            }
+        } else {
+            html! {}
+        };
 
         html! {
-            <div class="page">
+            <>
+                <div class="options">
+                    <select class="play-mode" onchange={set_play_mode}>
+                        <option value="daily" selected={play_mode == PlayMode::Daily}>{"Daily"}</option>
+                        <option value="random" selected={play_mode == PlayMode::Random}>{"Random"}</option>
+                        if has_custom {
+                            <option value="custom" selected={play_mode == PlayMode::Custom}>{"Custom"}</option>
+                        }
+                    </select>
+                    <select class="word-length" onchange={set_word_length}>
+                        { for WORD_LENGTHS.map(|len| html! {
+                            <option value={len.to_string()} selected={len == word_length}>
+                                {format!("{} letters", len)}
+                            </option>
+                        }) }
+                    </select>
+                    <select class="max-guesses" onchange={set_max_guesses}>
+                        { for GUESS_LIMITS.map(|max| html! {
+                            <option value={max.to_string()} selected={Some(max) == max_guesses}>
+                                {format!("{} guesses", max)}
+                            </option>
+                        }) }
+                        <option value="unlimited" selected={max_guesses.is_none()}>
+                            {"Unlimited guesses"}
+                        </option>
+                    </select>
+                    <span class="hard-mode-toggle" onclick={toggle_hard_mode}>
+                        {if self.settings.hard_mode { "Hard mode: on" } else { "Hard mode: off" }}
+                    </span>
+                    if matches!(self.game_mode, GameMode::Daily(_)) {
+                        <span class="daily-hints-toggle" onclick={toggle_daily_hints}>
+                            {if self.settings.daily_hints { "Daily hints: on" } else { "Daily hints: off" }}
+                        </span>
+                    }
+                    <span class="create-puzzle-button" onclick={create_puzzle}>{"Create puzzle"}</span>
+                    <span class="stats-button" onclick={show_stats}>{"Stats"}</span>
+                    <span class="sync-button" onclick={show_sync}>
+                        {if self.settings.sync.is_some() { "Sync: on" } else { "Sync: off" }}
+                    </span>
+                    if let Some(puzzle) = self.new_puzzle {
+                        <span class="new-puzzle-button" onclick={play_new_puzzle}>
+                            {format!("Play #{}", puzzle)}
+                        </span>
+                    }
+                </div>
                 <Board
-                    current_guess={self.current_guess.clone()}
-                    guesses={self.guesses.clone()}
-                    row_count={self.max_guesses}
-                    word_length={self.word_length}
+                    current_guess={self.game.current_guess().to_string()}
+                    guesses={self.game.guesses().to_vec()}
+                    row_count={self.row_count()}
+                    word_length={self.game.word_length()}
                 />
+                {hints}
                 <Keyboard key_press={cb} keys={self.keyboard_status.clone()} />
-                <BackdropViewer />
-                <ToastViewer />
-            </div>
+            </>
         }
     }
 
@@ -224,61 +1028,56 @@ impl Component for Paudle {
             return;
         }
 
-        if self.game_state != GameState::InProgress {
+        if self.game.is_over() {
             self.show_scoreboard(ctx);
         }
+        self.sync_url();
+        self.remember_play_mode();
+        self.sync_histories(ctx);
 
-        let on_keypress = ctx.link().batch_callback(handle_keypress);
-
-        let window = window().expect("No window? Where am I?");
-
-        EventListener::new(&window, "keydown", move |e: &Event| {
-            if let Ok(e) = e.clone().dyn_into::<KeyboardEvent>() {
-                on_keypress.emit(e);
-            }
-        })
-        .forget();
+        self.keydown_listener = Some(listen_for_keys(ctx.link().batch_callback(handle_keypress)));
+        self.storage_listener = self
+            .store
+            .watch(ctx.link().callback(|_| PaudleMsg::StorageChanged));
+        let tick = ctx.link().callback(|_: ()| PaudleMsg::Tick);
+        self.rollover_timer = Some(Interval::new(1000, move || tick.emit(())));
     }
 }
 
-fn evaluate_guess(word: &str, guess: &str) -> Vec<CellValue> {
-    let mut vals = Vec::with_capacity(word.len());
-    let mut counts = word
-        .chars()
-        .fold(HashMap::new(), |mut acc: HashMap<char, usize>, c| {
-            *acc.entry(c).or_insert(0) += 1;
-            acc
-        });
+/// Forwards the page's key presses to `on_keypress` until the listener is dropped.
+fn listen_for_keys(on_keypress: Callback<KeyboardEvent>) -> EventListener {
+    let window = window().expect("No window? Where am I?");
 
-    // find correct characters
-    for (w, g) in word.chars().zip(guess.chars()) {
-        let cell = if w == g {
-            if let Some(count) = counts.get_mut(&g) {
-                *count = count.saturating_sub(1);
-            }
-            Some(CellValue::Correct(g))
-        } else {
-            None
-        };
-        vals.push(cell);
-    }
+    EventListener::new(&window, "keydown", move |e: &Event| {
+        // Leave typing in text boxes alone
+        if e.target()
+            .and_then(|t| t.dyn_into::<HtmlInputElement>().ok())
+            .is_some()
+        {
+            return;
+        }
+        if let Ok(e) = e.clone().dyn_into::<KeyboardEvent>() {
+            on_keypress.emit(e);
+        }
+    })
+}
 
-    // categorize the rest of the characters
-    for (idx, g) in guess.chars().enumerate() {
-        let cell = match (vals[idx], counts.get(&g)) {
-            (v @ Some(_), _) => v,
-            (None, Some(f)) if *f > 0 => {
-                if let Some(count) = counts.get_mut(&g) {
-                    *count = count.saturating_sub(1);
-                }
-                Some(CellValue::Present(g))
-            }
-            (_, _) => Some(CellValue::Absent(g)),
-        };
-        vals[idx] = cell;
-    }
+fn toast_error(title: String) {
+    ToastDispatcher::new().toast(Toast {
+        title,
+        r#type: Type::Danger,
+        timeout: Some(Duration::from_secs(2)),
+        ..Toast::default()
+    });
+}
 
-    vals.into_iter().map(Option::unwrap).collect()
+fn toast_info(title: String) {
+    ToastDispatcher::new().toast(Toast {
+        title,
+        r#type: Type::Info,
+        timeout: Some(Duration::from_secs(4)),
+        ..Toast::default()
+    });
 }
 
 #[allow(clippy::needless_pass_by_value)]
@@ -310,5 +1109,5 @@ fn handle_keypress(e: KeyboardEvent) -> Option<PaudleMsg> {
 }
 
 fn main() {
-    yew::start_app::<Paudle>();
+    yew::start_app::<App>();
 }
@@ -0,0 +1,441 @@
+use gloo_events::EventListener;
+use gloo_timers::callback::Interval;
+use patternfly_yew::{Backdrop, BackdropDispatcher, Bullseye, Modal, ModalVariant};
+use paudle_core::{
+    schedule::{scheduled_word, seeded_words},
+    Evaluation, GameState, GuessError, MultiGame, DEFAULT_WORD_LENGTH, MULTI_EXTRA_GUESSES,
+};
+use yew::prelude::*;
+
+use crate::{
+    board::Board,
+    handle_keypress,
+    keyboard::{Keyboard, KeyboardStatus},
+    links::seed_tag,
+    listen_for_keys, new_seed,
+    save::{
+        load_multi_history, load_multi_random_history, load_multi_saved_state,
+        update_multi_saved_state,
+    },
+    scoreboard::{
+        generate_multi_score_copy, share_formats, ModeStats, Scoreboard, ScoreboardFooter, Stats,
+    },
+    settings::{load_settings, update_settings, PlayMode, Settings},
+    store::{SaveStore, Store},
+    toast_error, toast_info, todays_puzzle, GameMode, PaudleMsg,
+};
+
+pub fn multi_name(boards: usize) -> &'static str {
+    match boards {
+        2 => "Dordle",
+        4 => "Quordle",
+        8 => "Octordle",
+        _ => "Multordle",
+    }
+}
+
+pub struct MultiPaudle {
+    pub(crate) game: MultiGame,
+    keyboard_status: Vec<KeyboardStatus>,
+    pub(crate) game_mode: GameMode,
+    /// When the last guess was made, in seconds since the epoch.
+    pub(crate) finished_at: Option<i64>,
+    store: Store,
+    /// A daily puzzle that came out while this one was still being played.
+    new_puzzle: Option<u32>,
+    keydown_listener: Option<EventListener>,
+    storage_listener: Option<EventListener>,
+    rollover_timer: Option<Interval>,
+}
+
+/// Each board count has its own stream in the schedule, and takes the next `boards`
+/// words from it every day. A day can straddle two passes through the list, so a
+/// word already on another board is skipped.
+fn daily_words(boards: usize, puzzle: u32) -> Vec<String> {
+    let mut words = Vec::<String>::with_capacity(boards);
+    let mut idx = u64::from(puzzle) * boards as u64;
+    while words.len() < boards {
+        let word = scheduled_word(DEFAULT_WORD_LENGTH, boards as u64, idx).unwrap();
+        if !words.iter().any(|w| w == word) {
+            words.push(word.to_string());
+        }
+        idx += 1;
+    }
+    words
+}
+
+#[derive(Properties, PartialEq)]
+pub struct MultiPaudleProps {
+    pub boards: usize,
+    pub store: Store,
+}
+
+impl MultiPaudle {
+    pub fn with_words(words: Vec<String>, game_mode: GameMode, store: Store) -> Self {
+        Self {
+            keyboard_status: vec![KeyboardStatus::default(); words.len()],
+            game: MultiGame::new(words),
+            game_mode,
+            finished_at: None,
+            store,
+            new_puzzle: None,
+            keydown_listener: None,
+            storage_listener: None,
+            rollover_timer: None,
+        }
+    }
+
+    fn daily(boards: usize, store: Store) -> Self {
+        let puzzle = todays_puzzle();
+        Self::with_words(daily_words(boards, puzzle), GameMode::Daily(puzzle), store)
+    }
+
+    fn random(boards: usize, store: Store) -> Self {
+        let seed = new_seed();
+        let words = seeded_words(DEFAULT_WORD_LENGTH, seed, boards)
+            .unwrap()
+            .into_iter()
+            .map(ToString::to_string)
+            .collect();
+        Self::with_words(words, GameMode::Seeded(seed), store)
+    }
+
+    fn todays_game(boards: usize, store: Store) -> Self {
+        match load_multi_history(&store, boards)
+            .scores
+            .remove(&todays_puzzle())
+        {
+            Some(saved_state) => MultiPaudle::from_saved(saved_state, store),
+            None => MultiPaudle::daily(boards, store),
+        }
+    }
+
+    /// An unfinished daily game comes first, then an unfinished random one once
+    /// today's is done.
+    fn load(boards: usize, store: Store) -> Self {
+        if let Some(saved_state) = load_multi_saved_state(&store, PlayMode::Daily, boards) {
+            return MultiPaudle::from_saved(saved_state, store);
+        }
+        let today = MultiPaudle::todays_game(boards, store.clone());
+        match load_multi_saved_state(&store, PlayMode::Random, boards) {
+            Some(saved_state) if today.game.is_over() => {
+                MultiPaudle::from_saved(saved_state, store)
+            }
+            _ => today,
+        }
+    }
+
+    /// Swaps in a new game, keeping the page's listeners attached.
+    fn replace_game(&mut self, mut new_game: MultiPaudle) {
+        new_game.keydown_listener = self.keydown_listener.take();
+        new_game.storage_listener = self.storage_listener.take();
+        new_game.rollover_timer = self.rollover_timer.take();
+        *self = new_game;
+    }
+
+    /// Notices when a new daily puzzle comes out while the page is open, like
+    /// [`Paudle`](crate::Paudle) does.
+    fn check_for_new_puzzle(&mut self, ctx: &Context<Self>) -> bool {
+        let today = todays_puzzle();
+        match self.game_mode {
+            GameMode::Daily(puzzle) if puzzle != today && self.new_puzzle != Some(today) => {}
+            _ => return false,
+        }
+        toast_info(format!(
+            "{} #{} is out!",
+            multi_name(self.game.board_count()),
+            today
+        ));
+        if self.game.is_over() {
+            BackdropDispatcher::default().close();
+            self.play_new_puzzle(ctx);
+        } else {
+            self.new_puzzle = Some(today);
+        }
+        true
+    }
+
+    fn play_new_puzzle(&mut self, ctx: &Context<Self>) {
+        self.replace_game(MultiPaudle::todays_game(
+            self.game.board_count(),
+            self.store.clone(),
+        ));
+        update_multi_saved_state(&self.store, self);
+        if self.game.is_over() {
+            self.show_scoreboard(ctx);
+        }
+    }
+
+    /// Picks up what another tab did to the game being played, like
+    /// [`Paudle`](crate::Paudle) does.
+    fn merge_saved(&mut self, ctx: &Context<Self>) -> bool {
+        let boards = self.game.board_count();
+        if let (GameMode::Daily(puzzle), false) = (&self.game_mode, self.game.is_over()) {
+            let finished = load_multi_history(&self.store, boards)
+                .scores
+                .remove(puzzle);
+            if let Some(finished) = finished {
+                self.replace_game(MultiPaudle::from_saved(finished, self.store.clone()));
+                self.show_scoreboard(ctx);
+                return true;
+            }
+        }
+        match load_multi_saved_state(&self.store, self.game_mode.play_mode(), boards) {
+            Some(saved_state)
+                if saved_state.game_mode == self.game_mode
+                    && saved_state.guesses.len() > self.game.guesses().len() =>
+            {
+                self.replace_game(MultiPaudle::from_saved(saved_state, self.store.clone()));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn add_guess(&mut self, guess: &str) {
+        let evaluations = self.game.add_guess(guess);
+        self.update_keyboard(&evaluations);
+    }
+
+    fn update_keyboard(&mut self, evaluations: &[Option<Evaluation>]) {
+        for (status, evaluation) in self.keyboard_status.iter_mut().zip(evaluations) {
+            if let Some(evaluation) = evaluation {
+                status.update_status(evaluation);
+            }
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn show_scoreboard(&self, ctx: &Context<Self>) {
+        let clear = ctx.link().callback(|msg: PaudleMsg| msg);
+        let boards = self.game.board_count();
+        let won = self.game.state() == GameState::Won;
+        let title = if won {
+            "Winner!".to_string()
+        } else {
+            format!(
+                "Game Over. Words were {}",
+                self.game
+                    .words()
+                    .iter()
+                    .map(|w| format!(r#""{}""#, w))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        };
+        let name = match self.game_mode {
+            GameMode::Seeded(seed) => format!("{} {}", multi_name(boards), seed_tag(seed)),
+            GameMode::Daily(puzzle) => format!("{} #{}", multi_name(boards), puzzle),
+            GameMode::Custom(_) => multi_name(boards).to_string(),
+        };
+        let solved_in = (0..boards)
+            .map(|board| self.game.solved_in(board))
+            .collect::<Vec<_>>();
+        let share_texts = share_formats(|format| {
+            generate_multi_score_copy(
+                &name,
+                won,
+                self.game.guesses().len(),
+                self.game.max_guesses(),
+                &solved_in,
+                "",
+                format,
+            )
+        });
+        let guess_counts = boards..=boards + MULTI_EXTRA_GUESSES;
+        let mode_stats = |label: &str, daily: bool, history| {
+            let this_mode = daily == matches!(self.game_mode, GameMode::Daily(_));
+            ModeStats {
+                label: label.to_string(),
+                caption: if daily {
+                    multi_name(boards).to_string()
+                } else {
+                    format!("Random, {}", multi_name(boards))
+                },
+                stats: Stats::from_history(&history, guess_counts.clone()),
+                highlight: (this_mode && won).then(|| self.game.guesses().len()),
+            }
+        };
+        let modes = vec![
+            mode_stats("Daily", true, load_multi_history(&self.store, boards)),
+            mode_stats(
+                "Random",
+                false,
+                load_multi_random_history(&self.store, boards),
+            ),
+        ];
+        let selected = usize::from(!matches!(self.game_mode, GameMode::Daily(_)));
+        let bd = Backdrop {
+            content: html! {
+                <Bullseye>
+                    <Modal
+                        title={title}
+                        variant={ModalVariant::Small}
+                        footer={Some(html!{<ScoreboardFooter
+                                                share_texts={share_texts}
+                                                format={load_settings().share_format}
+                                                clear={clear}
+                                            />})}
+                    >
+                        <Scoreboard modes={modes} selected={selected} />
+                    </Modal>
+                </Bullseye>
+            },
+        };
+        BackdropDispatcher::default().open(bd);
+    }
+}
+
+impl Component for MultiPaudle {
+    type Message = PaudleMsg;
+
+    type Properties = MultiPaudleProps;
+
+    fn create(ctx: &Context<Self>) -> Self {
+        Self::load(ctx.props().boards, ctx.props().store.clone())
+    }
+
+    fn changed(&mut self, ctx: &Context<Self>) -> bool {
+        if ctx.props().boards == self.game.board_count() && ctx.props().store == self.store {
+            return false;
+        }
+        self.replace_game(Self::load(ctx.props().boards, ctx.props().store.clone()));
+        if self.game.is_over() {
+            self.show_scoreboard(ctx);
+        }
+        true
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match (self.game.is_over(), msg) {
+            (false, PaudleMsg::TypeLetter(c)) => self.game.type_letter(c),
+            (false, PaudleMsg::Backspace) => self.game.backspace(),
+            (false, PaudleMsg::Submit) => match self.game.submit() {
+                Ok(evaluations) => {
+                    self.update_keyboard(&evaluations);
+                    if self.game.is_over() {
+                        self.finished_at = Some(chrono::Utc::now().timestamp());
+                    }
+                    update_multi_saved_state(&self.store, self);
+                    if self.game.is_over() {
+                        self.show_scoreboard(ctx);
+                    }
+                    true
+                }
+                Err(e @ GuessError::NotInWordList) => {
+                    toast_error(e.to_string());
+                    true
+                }
+                Err(_) => false,
+            },
+            (true, PaudleMsg::StartRandom) => {
+                self.replace_game(MultiPaudle::random(
+                    self.game.board_count(),
+                    self.store.clone(),
+                ));
+                true
+            }
+            (_, PaudleMsg::SetShareFormat(share_format)) => {
+                update_settings(&Settings {
+                    share_format,
+                    ..load_settings()
+                });
+                false
+            }
+            (_, PaudleMsg::StorageChanged) => self.merge_saved(ctx),
+            (_, PaudleMsg::Tick) => self.check_for_new_puzzle(ctx),
+            (_, PaudleMsg::PlayNewPuzzle) => {
+                self.play_new_puzzle(ctx);
+                true
+            }
+            (_, PaudleMsg::Escape) => {
+                BackdropDispatcher::default().close();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let cb = ctx.link().callback(|msg: PaudleMsg| msg);
+        let boards = self.game.board_count();
+        let play_new_puzzle = ctx
+            .link()
+            .callback(|_: MouseEvent| PaudleMsg::PlayNewPuzzle);
+
+        html! {
+            <>
+                if let Some(puzzle) = self.new_puzzle {
+                    <div class="options">
+                        <span class="new-puzzle-button" onclick={play_new_puzzle}>
+                            {format!("Play #{}", puzzle)}
+                        </span>
+                    </div>
+                }
+                <div class="boards" data-boards={boards.to_string()}>
+                    { for (0..boards).map(|board| {
+                        let current_guess = if self.game.solved_in(board).is_some() {
+                            String::new()
+                        } else {
+                            self.game.current_guess().to_string()
+                        };
+                        html! {
+                            <Board
+                                current_guess={current_guess}
+                                guesses={self.game.boards()[board].clone()}
+                                row_count={self.game.max_guesses()}
+                                word_length={self.game.word_length()}
+                            />
+                        }
+                    }) }
+                </div>
+                <Keyboard
+                    key_press={cb}
+                    keys={KeyboardStatus::default()}
+                    boards={self.keyboard_status.clone()}
+                />
+            </>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if !first_render {
+            return;
+        }
+
+        if self.game.is_over() {
+            self.show_scoreboard(ctx);
+        }
+
+        self.keydown_listener = Some(listen_for_keys(ctx.link().batch_callback(handle_keypress)));
+        self.storage_listener = self
+            .store
+            .watch(ctx.link().callback(|_| PaudleMsg::StorageChanged));
+        let tick = ctx.link().callback(|_: ()| PaudleMsg::Tick);
+        self.rollover_timer = Some(Interval::new(1000, move || tick.emit(())));
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use paudle_core::{answer_list, DEFAULT_WORD_LENGTH, MULTI_BOARD_COUNTS};
+
+    use super::daily_words;
+
+    #[test]
+    fn test_daily_words_differ() {
+        let len = answer_list(DEFAULT_WORD_LENGTH).unwrap().lines().count();
+        for boards in MULTI_BOARD_COUNTS {
+            // the days either side of the end of the first pass through the list
+            let end = u32::try_from(len / boards).unwrap();
+            for puzzle in end - 1..=end + 1 {
+                let words = daily_words(boards, puzzle);
+                assert_eq!(words.len(), boards);
+                assert_eq!(words.iter().collect::<HashSet<_>>().len(), boards);
+            }
+        }
+    }
+}
@@ -1,84 +1,420 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, mem};
 
-use chrono::NaiveDateTime;
-use gloo_storage::{LocalStorage, Storage};
-use serde::{Deserialize, Serialize};
-use web_sys::console;
+use chrono::TimeZone;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{json, Value};
 
-use crate::{GameMode, GameState};
+use paudle_core::{
+    puzzle_number, CellValue, Game, DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH, MULTI_BOARD_COUNTS,
+    MULTI_EXTRA_GUESSES, WORD_LENGTHS,
+};
 
-use super::{board::CellValue, Paudle};
+use crate::{
+    absurdle::AbsurdlePaudle,
+    custom::encode_word,
+    keyboard::KeyboardStatus,
+    multi::MultiPaudle,
+    settings::{default_max_guesses, load_settings, PlayMode},
+    store::{SaveStore, Store},
+    versioned::{self, Migration, Schema},
+    GameMode, GUESS_LIMITS,
+};
 
-pub const SAVE_KEY: &str = "paudle_save_v1";
-pub const HISTORY_KEY: &str = "paudle_history_v1";
+use super::Paudle;
+
+pub const SAVE_KEY: &str = "paudle_save_v2";
+/// The one save every kind of classic game shared before they had their own.
+pub const LEGACY_SAVE_KEY: &str = "paudle_save_v1";
+pub const HISTORY_KEY: &str = "paudle_history_v2";
+pub const RANDOM_HISTORY_KEY: &str = "paudle_random_history_v1";
+pub const CUSTOM_HISTORY_KEY: &str = "paudle_custom_history_v1";
+pub const MULTI_SAVE_KEY: &str = "paudle_multi_save_v1";
+pub const MULTI_HISTORY_KEY: &str = "paudle_multi_history_v2";
+pub const MULTI_RANDOM_HISTORY_KEY: &str = "paudle_multi_random_history_v1";
+/// Daily histories from before puzzle numbers, at version 1. They're migrated the
+/// first time the new history is loaded and left in place.
+pub const LEGACY_HISTORY_KEY: &str = "paudle_history_v1";
+pub const LEGACY_MULTI_HISTORY_KEY: &str = "paudle_multi_history_v1";
+
+/// Old saves stored the day's midnight timestamp where the puzzle number now goes.
+/// Timestamps are far bigger than any puzzle number we'll reach.
+const MAX_PUZZLE_NUMBER: u32 = 1_000_000;
+pub const ABSURDLE_SAVE_KEY: &str = "paudle_absurdle_save_v1";
+pub const ABSURDLE_HISTORY_KEY: &str = "paudle_absurdle_history_v1";
+
+pub const SAVE_SCHEMA: Schema = Schema {
+    version: 2,
+    migrations: &[save_v1_to_v2],
+};
+pub const HISTORY_SCHEMA: Schema = Schema {
+    version: 2,
+    migrations: &[history_v1_to_v2],
+};
+pub const RANDOM_HISTORY_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+pub const CUSTOM_HISTORY_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+pub const MULTI_SAVE_SCHEMA: Schema = Schema {
+    version: 2,
+    migrations: &[multi_save_v1_to_v2],
+};
+pub const MULTI_HISTORY_SCHEMA: Schema = Schema {
+    version: 2,
+    migrations: &[multi_history_v1_to_v2],
+};
+pub const MULTI_RANDOM_HISTORY_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+pub const ABSURDLE_SAVE_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+pub const ABSURDLE_HISTORY_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+
+/// Each word length and guess limit keeps its own history so streaks don't cross
+/// over. Five letter, six guess games use the original key.
+fn history_key(base: &str, word_length: usize, max_guesses: Option<usize>) -> String {
+    let mut key = base.to_string();
+    if word_length != DEFAULT_WORD_LENGTH {
+        key.push_str(&format!("_{}", word_length));
+    }
+    match max_guesses {
+        Some(DEFAULT_MAX_GUESSES) => {}
+        Some(max) => key.push_str(&format!("_g{}", max)),
+        None => key.push_str("_unlimited"),
+    }
+    key
+}
+
+/// Daily and random games keep a save for each word length. Custom puzzles come
+/// with their own word length, so they share one.
+fn save_key(play_mode: PlayMode, word_length: usize) -> String {
+    let slot = match play_mode {
+        PlayMode::Daily => "daily",
+        PlayMode::Random => "random",
+        PlayMode::Custom => return format!("{}_custom", SAVE_KEY),
+    };
+    if word_length == DEFAULT_WORD_LENGTH {
+        format!("{}_{}", SAVE_KEY, slot)
+    } else {
+        format!("{}_{}_{}", SAVE_KEY, slot, word_length)
+    }
+}
+
+/// What the history needs to know about a finished game.
+pub trait Outcome {
+    fn was_won(&self) -> bool;
+    fn guess_count(&self) -> usize;
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SaveState {
     pub word: String,
     pub guesses: Vec<Vec<CellValue>>,
     pub game_mode: GameMode,
+    #[serde(default)]
+    pub hard_mode: bool,
+    #[serde(default = "default_max_guesses")]
+    pub max_guesses: Option<usize>,
+    #[serde(default)]
+    pub hints_used: bool,
+    /// When the first letter was typed, in seconds since the epoch.
+    #[serde(default)]
+    pub started_at: Option<i64>,
+    /// When the last guess was made, in seconds since the epoch.
+    #[serde(default)]
+    pub finished_at: Option<i64>,
 }
 
 impl SaveState {
     pub fn from_live(from: &Paudle) -> Self {
         Self {
-            word: from.word.clone(),
-            guesses: from.guesses.clone(),
+            word: from.game.word().to_string(),
+            guesses: from.game.guesses().to_vec(),
             game_mode: from.game_mode.clone(),
+            hard_mode: from.game.hard_mode(),
+            max_guesses: from.game.max_guesses(),
+            hints_used: from.hints_used,
+            started_at: from.started_at,
+            finished_at: from.finished_at,
         }
     }
 
-    pub fn was_won(&self) -> bool {
-        self.guesses[self.guesses.len() - 1]
-            .iter()
-            .map(|v| match v {
-                CellValue::Typing(c)
-                | CellValue::Absent(c)
-                | CellValue::Present(c)
-                | CellValue::Correct(c) => c,
-                CellValue::Empty => &' ',
-            })
-            .collect::<String>()
-            == self.word
+    /// Whether this is a finished game this game could have played, with a word
+    /// length and guess limit it offers. Games from elsewhere, like imports and
+    /// other devices, have to pass before they go in a history.
+    pub fn is_recordable(&self) -> bool {
+        let word_length = self.word.chars().count();
+        let playable = WORD_LENGTHS.contains(&word_length)
+            && self.word.chars().all(|c| c.is_ascii_lowercase())
+            && self
+                .max_guesses
+                .is_none_or(|max| GUESS_LIMITS.contains(&max) && self.guesses.len() <= max)
+            && self.guesses.iter().all(|row| row.len() == word_length);
+        let finished = !self.guesses.is_empty()
+            && (self.was_won()
+                || self
+                    .max_guesses
+                    .is_some_and(|max| self.guess_count() >= max));
+        playable && finished
     }
 }
 
-pub fn update_saved_state(live: &Paudle) {
-    if live.game_state == GameState::InProgress {
-        if let Err(e) = LocalStorage::set(SAVE_KEY, SaveState::from_live(live)) {
-            console::log_1(&format!("Couldn't save game state: {}", e).into());
-        }
+impl Outcome for SaveState {
+    fn was_won(&self) -> bool {
+        self.guesses.last().is_some_and(|last| {
+            last.iter()
+                .map(|v| match v {
+                    CellValue::Typing(c)
+                    | CellValue::Absent(c)
+                    | CellValue::Present(c)
+                    | CellValue::Correct(c) => c,
+                    CellValue::Empty => &' ',
+                })
+                .collect::<String>()
+                == self.word
+        })
+    }
+
+    fn guess_count(&self) -> usize {
+        self.guesses.len()
+    }
+}
+
+pub fn update_saved_state(store: &dyn SaveStore, live: &Paudle) {
+    let word_length = live.game.word_length();
+    let key = save_key(live.game_mode.play_mode(), word_length);
+    if !live.game.is_over() {
+        versioned::store(store, &key, &SaveState::from_live(live), &SAVE_SCHEMA);
     } else {
-        if let GameMode::Daily(ts) = live.game_mode {
-            let mut history = load_game_history();
-            history.scores.insert(ts, SaveState::from_live(live));
-            if let Err(e) = LocalStorage::set(HISTORY_KEY, history) {
-                console::log_1(&format!("Couldn't save game history: {}", e).into());
-            }
+        let max_guesses = live.game.max_guesses();
+        let play_mode = live.game_mode.play_mode();
+        let mut history = load_history(store, play_mode, word_length, max_guesses);
+        let number = history_number(&history, &live.game_mode, |s| &s.game_mode);
+        // A daily result is final, even if another tab finished the same puzzle
+        // first
+        let recorded_elsewhere =
+            matches!(live.game_mode, GameMode::Daily(_)) && history.scores.contains_key(&number);
+        if !recorded_elsewhere {
+            history.scores.insert(number, SaveState::from_live(live));
+            store_history(store, play_mode, word_length, max_guesses, &history);
         }
-        LocalStorage::delete(SAVE_KEY);
+        store.remove(&key);
     }
 }
 
-pub fn load_saved_sate() -> Option<SaveState> {
-    let save_state: gloo_storage::Result<SaveState> = LocalStorage::get(SAVE_KEY);
-    match save_state {
-        Ok(save_state) => Some(save_state),
-        Err(gloo_storage::errors::StorageError::KeyNotFound(_)) => None,
-        Err(e) => {
-            console::log_1(&format!("Found game state but couldn't deserialize: {}", e).into());
-            LocalStorage::delete(SAVE_KEY);
-            None
+/// Where `game_mode` goes in its history. Random games and custom puzzles are
+/// numbered in the order they're played, and replaying one keeps its original number.
+fn history_number<T>(
+    history: &GameHistory<T>,
+    game_mode: &GameMode,
+    mode_of: impl Fn(&T) -> &GameMode,
+) -> u32 {
+    match game_mode {
+        GameMode::Daily(puzzle) => *puzzle,
+        GameMode::Seeded(_) | GameMode::Custom(_) => history
+            .scores
+            .iter()
+            .find(|(_, s)| mode_of(s) == game_mode)
+            .map(|(n, _)| *n)
+            .unwrap_or_else(|| next_history_number(history)),
+    }
+}
+
+fn next_history_number<T>(history: &GameHistory<T>) -> u32 {
+    history.scores.keys().max().map_or(0, |n| n + 1)
+}
+
+fn store_history(
+    store: &dyn SaveStore,
+    play_mode: PlayMode,
+    word_length: usize,
+    max_guesses: Option<usize>,
+    history: &GameHistory,
+) {
+    let (base, schema) = history_location(play_mode);
+    versioned::store(
+        store,
+        &history_key(base, word_length, max_guesses),
+        history,
+        schema,
+    );
+}
+
+/// What became of a game merged into its history.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Merged {
+    Added,
+    /// The same game was already recorded.
+    Duplicate,
+    /// The game was already recorded with different guesses, which were kept.
+    Conflict,
+}
+
+/// Adds a finished game from somewhere else, such as an import, to its history.
+/// Nothing already recorded is overwritten.
+pub fn merge_into_history(store: &dyn SaveStore, game: SaveState) -> Merged {
+    let play_mode = game.game_mode.play_mode();
+    let word_length = game.word.chars().count();
+    let mut history = load_history(store, play_mode, word_length, game.max_guesses);
+    let number = history_number(&history, &game.game_mode, |s| &s.game_mode);
+    match history.scores.get(&number) {
+        Some(existing) if existing.guesses == game.guesses => Merged::Duplicate,
+        Some(_) => Merged::Conflict,
+        None => {
+            let max_guesses = game.max_guesses;
+            history.scores.insert(number, game);
+            store_history(store, play_mode, word_length, max_guesses, &history);
+            Merged::Added
+        }
+    }
+}
+
+/// The game left unfinished in `play_mode` at `word_length`, if there is one.
+pub fn load_saved_sate(
+    store: &dyn SaveStore,
+    play_mode: PlayMode,
+    word_length: usize,
+) -> Option<SaveState> {
+    move_legacy_save(store);
+    versioned::load(store, &save_key(play_mode, word_length), &SAVE_SCHEMA, 2)
+}
+
+pub fn has_saved_state(store: &dyn SaveStore, play_mode: PlayMode, word_length: usize) -> bool {
+    move_legacy_save(store);
+    store.get(&save_key(play_mode, word_length)).is_some()
+}
+
+/// Moves the save from before there were slots into the slot for its game, unless
+/// something has been saved there since.
+fn move_legacy_save(store: &dyn SaveStore) {
+    if let Some(saved_state) = versioned::load::<SaveState>(store, LEGACY_SAVE_KEY, &SAVE_SCHEMA, 1)
+    {
+        let key = save_key(
+            saved_state.game_mode.play_mode(),
+            saved_state.word.chars().count(),
+        );
+        if store.get(&key).is_none() {
+            versioned::store(store, &key, &saved_state, &SAVE_SCHEMA);
         }
+        store.remove(LEGACY_SAVE_KEY);
     }
 }
 
-impl From<SaveState> for Paudle {
-    fn from(other: SaveState) -> Self {
+/// Version 1 daily games were keyed by the day's midnight timestamp, and random
+/// games didn't keep a seed. `random` is what a random game becomes now.
+fn game_mode_v1_to_v2(game_mode: &Value, random: Value) -> Value {
+    match game_mode {
+        Value::String(mode) if mode == "Random" => random,
+        Value::Object(mode) => match mode.get("Daily").and_then(Value::as_i64) {
+            Some(ts) if ts > i64::from(MAX_PUZZLE_NUMBER) => {
+                json!({ "Daily": puzzle_for_timestamp(ts).unwrap_or(0) })
+            }
+            _ => game_mode.clone(),
+        },
+        _ => game_mode.clone(),
+    }
+}
+
+fn save_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    let save = data.as_object_mut().ok_or("saved game isn't an object")?;
+    let word = save
+        .get("word")
+        .and_then(Value::as_str)
+        .ok_or("saved game has no word")?;
+    // The seed wasn't kept, so a random game carries on as a puzzle of its word
+    let random = json!({ "Custom": encode_word(word) });
+    let game_mode = save.get("game_mode").ok_or("saved game has no mode")?;
+    let game_mode = game_mode_v1_to_v2(game_mode, random);
+    save.insert("game_mode".into(), game_mode);
+    Ok(data)
+}
+
+fn multi_save_v1_to_v2(mut data: Value) -> Result<Value, String> {
+    let save = data.as_object_mut().ok_or("saved game isn't an object")?;
+    let words = save
+        .get("words")
+        .and_then(Value::as_array)
+        .ok_or("saved game has no words")?
+        .iter()
+        .map(|w| {
+            w.as_str()
+                .ok_or("saved game has a word that isn't a string")
+        })
+        .collect::<Result<String, _>>()?;
+    // The seed wasn't kept but the words were, so the game carries on as a puzzle
+    // of its words rather than claim a seed that would give others
+    let random = json!({ "Custom": encode_word(&words) });
+    let game_mode = save.get("game_mode").ok_or("saved game has no mode")?;
+    let game_mode = game_mode_v1_to_v2(game_mode, random);
+    save.insert("game_mode".into(), game_mode);
+    Ok(data)
+}
+
+/// Rekeys a version 1 history by puzzle number. Entries that land on the same day,
+/// as happened around daylight saving changes, collapse into the later one.
+fn rekey_history(mut data: Value, migrate_entry: Migration) -> Result<Value, String> {
+    let scores = data
+        .get_mut("scores")
+        .and_then(Value::as_object_mut)
+        .ok_or("history has no scores")?;
+    for (ts, entry) in mem::take(scores) {
+        let ts = ts.parse::<i64>().map_err(|e| e.to_string())?;
+        // Nothing was played before the schedule started
+        let Some(puzzle) = puzzle_for_timestamp(ts) else {
+            continue;
+        };
+        let mut entry = migrate_entry(entry)?;
+        entry["game_mode"] = json!({ "Daily": puzzle });
+        scores.insert(puzzle.to_string(), entry);
+    }
+    Ok(data)
+}
+
+fn history_v1_to_v2(data: Value) -> Result<Value, String> {
+    rekey_history(data, save_v1_to_v2)
+}
+
+fn multi_history_v1_to_v2(data: Value) -> Result<Value, String> {
+    rekey_history(data, multi_save_v1_to_v2)
+}
+
+/// The puzzle for the day an old history entry was keyed by. Keys were meant to be
+/// local midnight, but daylight saving changes and travel shifted them by a few
+/// hours either way, so go by midday.
+fn puzzle_for_timestamp(ts: i64) -> Option<u32> {
+    let midday = chrono::Local
+        .timestamp_opt(ts + 12 * 60 * 60, 0)
+        .single()?
+        .naive_local();
+    puzzle_number(midday.date())
+}
+
+impl Paudle {
+    pub fn from_saved(other: SaveState, store: Store) -> Self {
+        let mut game = Game::new(other.word).with_max_guesses(other.max_guesses);
+        game.set_hard_mode(other.hard_mode);
         let mut new = Self {
-            word: other.word,
+            game,
             game_mode: other.game_mode,
-            ..Paudle::default()
+            keyboard_status: KeyboardStatus::default(),
+            settings: load_settings(),
+            hints_used: other.hints_used,
+            started_at: other.started_at,
+            finished_at: other.finished_at,
+            store,
+            new_puzzle: None,
+            keydown_listener: None,
+            storage_listener: None,
+            rollover_timer: None,
         };
 
         other.guesses.into_iter().for_each(|g| new.add_guess(g));
@@ -87,12 +423,21 @@ impl From<SaveState> for Paudle {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct GameHistory {
-    pub scores: HashMap<i64, SaveState>,
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GameHistory<T = SaveState> {
+    /// Daily games by puzzle number, or random games in the order they were played.
+    pub scores: HashMap<u32, T>,
+}
+
+impl<T> Default for GameHistory<T> {
+    fn default() -> Self {
+        Self {
+            scores: HashMap::new(),
+        }
+    }
 }
 
-impl GameHistory {
+impl<T: Outcome> GameHistory<T> {
     pub fn wins(&self) -> usize {
         self.scores.values().filter(|v| v.was_won()).count()
     }
@@ -101,12 +446,12 @@ impl GameHistory {
         self.scores
             .keys()
             .max()
-            .and_then(|k| self.scores.get(k).map(SaveState::was_won))
+            .and_then(|k| self.scores.get(k).map(Outcome::was_won))
             .unwrap_or(false)
     }
 
     pub fn current_streak(&self) -> usize {
-        let last_streak = *self.streaks().get(0).unwrap_or(&0);
+        let last_streak = *self.streaks().first().unwrap_or(&0);
 
         if self.won_last() {
             last_streak
@@ -120,21 +465,19 @@ impl GameHistory {
     }
 
     fn streaks(&self) -> Vec<usize> {
-        let mut dates = self
+        let mut puzzles = self
             .scores
             .iter()
-            .map(|(k, v)| (chrono::NaiveDateTime::from_timestamp(*k, 0), v.was_won()))
+            .map(|(k, v)| (*k, v.was_won()))
             .collect::<Vec<_>>();
-        dates.sort_unstable_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
-
-        let dates = dates.into_iter().rev().collect::<Vec<_>>();
+        puzzles.sort_unstable_by_key(|(k, _)| std::cmp::Reverse(*k));
 
         let mut count = 0;
         let mut prev = None;
         let mut streaks = Vec::new();
 
-        for (date, won) in &dates {
-            if prev.map(|p: &NaiveDateTime| *p - *date > chrono::Duration::days(1)) == Some(true) {
+        for (puzzle, won) in &puzzles {
+            if prev.is_some_and(|p: &u32| p - puzzle > 1) {
                 if count > 0 {
                     streaks.push(count);
                 }
@@ -148,7 +491,7 @@ impl GameHistory {
                 }
                 count = 0;
             }
-            prev = Some(date);
+            prev = Some(puzzle);
         }
 
         if count > 0 {
@@ -159,30 +502,524 @@ impl GameHistory {
     }
 }
 
-pub fn load_game_history() -> GameHistory {
-    let history: gloo_storage::Result<GameHistory> = LocalStorage::get(HISTORY_KEY);
-    match history {
-        Ok(history) => history,
-        Err(gloo_storage::errors::StorageError::KeyNotFound(_)) => GameHistory::default(),
-        Err(e) => {
-            console::log_1(&format!("Found game history but couldn't deserialize: {}", e).into());
-            LocalStorage::delete(HISTORY_KEY);
-            GameHistory::default()
+pub fn load_game_history(
+    store: &dyn SaveStore,
+    word_length: usize,
+    max_guesses: Option<usize>,
+) -> GameHistory {
+    load_daily_history(
+        store,
+        &history_key(HISTORY_KEY, word_length, max_guesses),
+        &history_key(LEGACY_HISTORY_KEY, word_length, max_guesses),
+        &HISTORY_SCHEMA,
+    )
+}
+
+pub fn load_random_history(
+    store: &dyn SaveStore,
+    word_length: usize,
+    max_guesses: Option<usize>,
+) -> GameHistory {
+    versioned::load(
+        store,
+        &history_key(RANDOM_HISTORY_KEY, word_length, max_guesses),
+        &RANDOM_HISTORY_SCHEMA,
+        1,
+    )
+    .unwrap_or_default()
+}
+
+pub fn load_custom_history(
+    store: &dyn SaveStore,
+    word_length: usize,
+    max_guesses: Option<usize>,
+) -> GameHistory {
+    versioned::load(
+        store,
+        &history_key(CUSTOM_HISTORY_KEY, word_length, max_guesses),
+        &CUSTOM_HISTORY_SCHEMA,
+        1,
+    )
+    .unwrap_or_default()
+}
+
+/// Every finished game of one kind, word length and guess limit.
+pub fn load_history(
+    store: &dyn SaveStore,
+    play_mode: PlayMode,
+    word_length: usize,
+    max_guesses: Option<usize>,
+) -> GameHistory {
+    match play_mode {
+        PlayMode::Daily => load_game_history(store, word_length, max_guesses),
+        PlayMode::Random => load_random_history(store, word_length, max_guesses),
+        PlayMode::Custom => load_custom_history(store, word_length, max_guesses),
+    }
+}
+
+/// A daily history. These are the histories that can be synced between devices,
+/// since a puzzle number means the same game everywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DailyHistory {
+    Classic {
+        word_length: usize,
+        max_guesses: Option<usize>,
+    },
+    Multi {
+        boards: usize,
+    },
+}
+
+impl DailyHistory {
+    pub fn all() -> Vec<Self> {
+        let classic = WORD_LENGTHS.flat_map(|word_length| {
+            GUESS_LIMITS
+                .map(Some)
+                .chain([None])
+                .map(move |max_guesses| DailyHistory::Classic {
+                    word_length,
+                    max_guesses,
+                })
+        });
+        let multi = MULTI_BOARD_COUNTS
+            .into_iter()
+            .map(|boards| DailyHistory::Multi { boards });
+        classic.chain(multi).collect()
+    }
+
+    pub fn key(self) -> String {
+        match self {
+            DailyHistory::Classic {
+                word_length,
+                max_guesses,
+            } => history_key(HISTORY_KEY, word_length, max_guesses),
+            DailyHistory::Multi { boards } => multi_history_key(MULTI_HISTORY_KEY, boards),
+        }
+    }
+
+    pub fn schema(self) -> &'static Schema {
+        match self {
+            DailyHistory::Classic { .. } => &HISTORY_SCHEMA,
+            DailyHistory::Multi { .. } => &MULTI_HISTORY_SCHEMA,
         }
     }
+
+    /// Whether `game`, from another device, is a finished game of this kind for
+    /// `puzzle`. Anything else has to be left out, since a single game that doesn't
+    /// load stops the whole history loading.
+    pub fn accepts(self, puzzle: u32, game: &Value) -> bool {
+        let daily = GameMode::Daily(puzzle);
+        match self {
+            DailyHistory::Classic {
+                word_length,
+                max_guesses,
+            } => serde_json::from_value::<SaveState>(game.clone()).is_ok_and(|game| {
+                game.game_mode == daily
+                    && game.word.chars().count() == word_length
+                    && game.max_guesses == max_guesses
+                    && game.is_recordable()
+            }),
+            DailyHistory::Multi { boards } => {
+                serde_json::from_value::<MultiSaveState>(game.clone()).is_ok_and(|game| {
+                    game.game_mode == daily && game.words.len() == boards && game.is_recordable()
+                })
+            }
+        }
+    }
+}
+
+fn history_location(play_mode: PlayMode) -> (&'static str, &'static Schema) {
+    match play_mode {
+        PlayMode::Daily => (HISTORY_KEY, &HISTORY_SCHEMA),
+        PlayMode::Random => (RANDOM_HISTORY_KEY, &RANDOM_HISTORY_SCHEMA),
+        PlayMode::Custom => (CUSTOM_HISTORY_KEY, &CUSTOM_HISTORY_SCHEMA),
+    }
+}
+
+/// Loads a daily history, migrating it from `legacy_key` the first time. Histories
+/// stored under `key` before envelopes were already at version 2.
+fn load_daily_history<T: Serialize + DeserializeOwned>(
+    store: &dyn SaveStore,
+    key: &str,
+    legacy_key: &str,
+    schema: &Schema,
+) -> GameHistory<T> {
+    if let Some(history) = versioned::load(store, key, schema, 2) {
+        return history;
+    }
+    match versioned::load(store, legacy_key, schema, 1) {
+        Some(history) => {
+            versioned::store(store, key, &history, schema);
+            history
+        }
+        None => GameHistory::default(),
+    }
+}
+
+/// Multi-board games only keep the guessed words; the boards are rebuilt from them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MultiSaveState {
+    pub words: Vec<String>,
+    pub guesses: Vec<String>,
+    pub game_mode: GameMode,
+    /// When the last guess was made, in seconds since the epoch. Syncing goes by
+    /// this, as it does for classic games.
+    #[serde(default)]
+    pub finished_at: Option<i64>,
+}
+
+impl MultiSaveState {
+    pub fn from_live(from: &MultiPaudle) -> Self {
+        Self {
+            words: from.game.words().to_vec(),
+            guesses: from.game.guesses().to_vec(),
+            game_mode: from.game_mode.clone(),
+            finished_at: from.finished_at,
+        }
+    }
+
+    /// Whether this is a finished game this game could have played, like
+    /// [`SaveState::is_recordable`].
+    pub fn is_recordable(&self) -> bool {
+        let boards = self.words.len();
+        let is_word = |w: &String| {
+            w.len() == DEFAULT_WORD_LENGTH && w.chars().all(|c| c.is_ascii_lowercase())
+        };
+        let playable = MULTI_BOARD_COUNTS.contains(&boards)
+            && self.words.iter().chain(&self.guesses).all(is_word)
+            && self.guesses.len() <= boards + MULTI_EXTRA_GUESSES;
+        playable && (self.was_won() || self.guesses.len() == boards + MULTI_EXTRA_GUESSES)
+    }
+}
+
+impl Outcome for MultiSaveState {
+    fn was_won(&self) -> bool {
+        self.words.iter().all(|w| self.guesses.contains(w))
+    }
+
+    fn guess_count(&self) -> usize {
+        self.guesses.len()
+    }
+}
+
+/// Daily and random multi-board games keep a save for each board count. Random
+/// and custom games share the random slot.
+fn multi_save_key(play_mode: PlayMode, boards: usize) -> String {
+    let slot = match play_mode {
+        PlayMode::Daily => "daily",
+        PlayMode::Random | PlayMode::Custom => "random",
+    };
+    format!("{}_{}_{}", MULTI_SAVE_KEY, slot, boards)
+}
+
+/// Moves the save from before there were slots into the slot for its game, unless
+/// something has been saved there since, like [`move_legacy_save`].
+fn move_legacy_multi_save(store: &dyn SaveStore, boards: usize) {
+    let legacy_key = format!("{}_{}", MULTI_SAVE_KEY, boards);
+    if let Some(saved_state) =
+        versioned::load::<MultiSaveState>(store, &legacy_key, &MULTI_SAVE_SCHEMA, 1)
+    {
+        let key = multi_save_key(saved_state.game_mode.play_mode(), boards);
+        if store.get(&key).is_none() {
+            versioned::store(store, &key, &saved_state, &MULTI_SAVE_SCHEMA);
+        }
+        store.remove(&legacy_key);
+    }
+}
+
+fn multi_history_key(base: &str, boards: usize) -> String {
+    format!("{}_{}", base, boards)
+}
+
+pub fn update_multi_saved_state(store: &dyn SaveStore, live: &MultiPaudle) {
+    let boards = live.game.board_count();
+    let save_key = multi_save_key(live.game_mode.play_mode(), boards);
+    if !live.game.is_over() {
+        versioned::store(
+            store,
+            &save_key,
+            &MultiSaveState::from_live(live),
+            &MULTI_SAVE_SCHEMA,
+        );
+    } else {
+        let (mut history, key, schema) = load_multi_history_for(store, &live.game_mode, boards);
+        let number = history_number(&history, &live.game_mode, |s| &s.game_mode);
+        history
+            .scores
+            .insert(number, MultiSaveState::from_live(live));
+        versioned::store(store, &key, &history, schema);
+        store.remove(&save_key);
+    }
+}
+
+/// The history a multi-board game in `game_mode` goes in, with where to store it.
+fn load_multi_history_for(
+    store: &dyn SaveStore,
+    game_mode: &GameMode,
+    boards: usize,
+) -> (GameHistory<MultiSaveState>, String, &'static Schema) {
+    match game_mode {
+        GameMode::Daily(_) => (
+            load_multi_history(store, boards),
+            multi_history_key(MULTI_HISTORY_KEY, boards),
+            &MULTI_HISTORY_SCHEMA,
+        ),
+        GameMode::Seeded(_) | GameMode::Custom(_) => (
+            load_multi_random_history(store, boards),
+            multi_history_key(MULTI_RANDOM_HISTORY_KEY, boards),
+            &MULTI_RANDOM_HISTORY_SCHEMA,
+        ),
+    }
+}
+
+/// Adds a finished multi-board game from somewhere else to its history, like
+/// [`merge_into_history`].
+pub fn merge_into_multi_history(store: &dyn SaveStore, game: MultiSaveState) -> Merged {
+    let (mut history, key, schema) =
+        load_multi_history_for(store, &game.game_mode, game.words.len());
+    let number = history_number(&history, &game.game_mode, |s| &s.game_mode);
+    match history.scores.get(&number) {
+        Some(existing) if existing.guesses == game.guesses => Merged::Duplicate,
+        Some(_) => Merged::Conflict,
+        None => {
+            history.scores.insert(number, game);
+            versioned::store(store, &key, &history, schema);
+            Merged::Added
+        }
+    }
+}
+
+/// Finished random multi-board games, in the order they were played.
+pub fn load_multi_random_history(
+    store: &dyn SaveStore,
+    boards: usize,
+) -> GameHistory<MultiSaveState> {
+    versioned::load(
+        store,
+        &multi_history_key(MULTI_RANDOM_HISTORY_KEY, boards),
+        &MULTI_RANDOM_HISTORY_SCHEMA,
+        1,
+    )
+    .unwrap_or_default()
+}
+
+pub fn load_multi_saved_state(
+    store: &dyn SaveStore,
+    play_mode: PlayMode,
+    boards: usize,
+) -> Option<MultiSaveState> {
+    move_legacy_multi_save(store, boards);
+    versioned::load(
+        store,
+        &multi_save_key(play_mode, boards),
+        &MULTI_SAVE_SCHEMA,
+        1,
+    )
+}
+
+pub fn load_multi_history(store: &dyn SaveStore, boards: usize) -> GameHistory<MultiSaveState> {
+    load_daily_history(
+        store,
+        &multi_history_key(MULTI_HISTORY_KEY, boards),
+        &multi_history_key(LEGACY_MULTI_HISTORY_KEY, boards),
+        &MULTI_HISTORY_SCHEMA,
+    )
+}
+
+impl MultiPaudle {
+    pub fn from_saved(other: MultiSaveState, store: Store) -> Self {
+        let mut new = Self::with_words(other.words, other.game_mode, store);
+        other.guesses.iter().for_each(|g| new.add_guess(g));
+        new.finished_at = other.finished_at;
+        new
+    }
+}
+
+/// Absurdle games have no answer to save, only the guesses that narrowed down the
+/// candidates. Finished games go to their own history, in the order they're
+/// played.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AbsurdleSaveState {
+    pub guesses: Vec<String>,
+}
+
+impl AbsurdleSaveState {
+    pub fn from_live(from: &AbsurdlePaudle) -> Self {
+        Self {
+            guesses: from
+                .game
+                .guesses()
+                .iter()
+                .map(|g| {
+                    g.iter()
+                        .filter_map(|c| match c {
+                            CellValue::Absent(c)
+                            | CellValue::Present(c)
+                            | CellValue::Correct(c) => Some(c),
+                            CellValue::Empty | CellValue::Typing(_) => None,
+                        })
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+}
+
+impl Outcome for AbsurdleSaveState {
+    /// Games only end once the word is cornered.
+    fn was_won(&self) -> bool {
+        true
+    }
+
+    fn guess_count(&self) -> usize {
+        self.guesses.len()
+    }
+}
+
+pub fn update_absurdle_saved_state(store: &dyn SaveStore, live: &AbsurdlePaudle) {
+    if live.game.is_over() {
+        let mut history = load_absurdle_history(store);
+        history.scores.insert(
+            next_history_number(&history),
+            AbsurdleSaveState::from_live(live),
+        );
+        versioned::store(
+            store,
+            ABSURDLE_HISTORY_KEY,
+            &history,
+            &ABSURDLE_HISTORY_SCHEMA,
+        );
+        store.remove(ABSURDLE_SAVE_KEY);
+    } else {
+        versioned::store(
+            store,
+            ABSURDLE_SAVE_KEY,
+            &AbsurdleSaveState::from_live(live),
+            &ABSURDLE_SAVE_SCHEMA,
+        );
+    }
+}
+
+pub fn load_absurdle_history(store: &dyn SaveStore) -> GameHistory<AbsurdleSaveState> {
+    versioned::load(store, ABSURDLE_HISTORY_KEY, &ABSURDLE_HISTORY_SCHEMA, 1).unwrap_or_default()
+}
+
+pub fn load_absurdle_saved_state(store: &dyn SaveStore) -> Option<AbsurdleSaveState> {
+    versioned::load(store, ABSURDLE_SAVE_KEY, &ABSURDLE_SAVE_SCHEMA, 1)
+}
+
+impl AbsurdlePaudle {
+    pub fn from_saved(other: AbsurdleSaveState, store: Store) -> Self {
+        let mut new = Self::new(store);
+        other.guesses.iter().for_each(|g| new.add_guess(g));
+        new
+    }
 }
 
 #[cfg(test)]
-mod test {
-    use chrono::NaiveDate;
+pub(crate) mod test {
+    use chrono::{Local, TimeZone, Utc};
+    use paudle_core::CellValue;
 
-    use crate::{board::CellValue, GameMode};
+    use paudle_core::AbsurdleGame;
 
-    use super::{GameHistory, SaveState};
+    use crate::{
+        absurdle::AbsurdlePaudle,
+        custom::encode_word,
+        multi::MultiPaudle,
+        settings::{PlayMode, Settings},
+        store::{MemoryStore, SaveStore, Store},
+        versioned::{decode, encode},
+        GameMode, Paudle,
+    };
+
+    use super::{
+        load_absurdle_history, load_game_history, load_history, load_multi_history,
+        load_multi_random_history, load_multi_saved_state, load_saved_sate,
+        update_absurdle_saved_state, update_multi_saved_state, update_saved_state, GameHistory,
+        MultiSaveState, Outcome, SaveState, HISTORY_SCHEMA, LEGACY_SAVE_KEY, MAX_PUZZLE_NUMBER,
+        MULTI_HISTORY_SCHEMA, MULTI_SAVE_KEY, MULTI_SAVE_SCHEMA, SAVE_SCHEMA,
+    };
+
+    /// A finished or unfinished game of `word`, with `guesses` as its rows.
+    pub(crate) fn saved_game(game_mode: GameMode, word: &str, guesses: &[&str]) -> SaveState {
+        SaveState {
+            word: word.into(),
+            guesses: guesses
+                .iter()
+                .map(|w| w.chars().map(CellValue::Absent).collect())
+                .collect(),
+            game_mode,
+            hard_mode: false,
+            max_guesses: Some(6),
+            hints_used: false,
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    /// Starts a classic game of `word` and plays `guesses` in it, saving after
+    /// each one as the page does.
+    fn play(store: &Store, game_mode: GameMode, word: &str, guesses: &[&str]) -> Paudle {
+        let mut live =
+            Paudle::with_word(word.into(), game_mode, Settings::default(), store.clone());
+        guess(store, &mut live, guesses);
+        live
+    }
+
+    /// Plays `guesses` in `live`, saving after each one.
+    fn guess(store: &Store, live: &mut Paudle, guesses: &[&str]) {
+        for guess in guesses {
+            guess.chars().for_each(|c| {
+                live.game.type_letter(c);
+            });
+            live.game.submit().unwrap();
+            update_saved_state(store, live);
+        }
+    }
+
+    /// Starts a multi-board game of `words` and plays `guesses` in it, saving once
+    /// they're in.
+    fn play_multi(store: &Store, game_mode: GameMode, words: &[&str], guesses: &[&str]) {
+        let words = words.iter().map(|w| w.to_string()).collect();
+        let mut live = MultiPaudle::with_words(words, game_mode, store.clone());
+        guesses.iter().for_each(|g| live.add_guess(g));
+        update_multi_saved_state(store, &live);
+    }
+
+    /// The fixtures were written in UTC, but old saves were keyed by local time.
+    /// Moves every timestamp in `fixture` to the same time of day here, so the
+    /// migrations see what they would have in any timezone.
+    fn localized(fixture: &str) -> String {
+        let local = |ts: i64| {
+            let utc = Utc.timestamp_opt(ts, 0).unwrap().naive_utc();
+            Local
+                .from_local_datetime(&utc)
+                .earliest()
+                .unwrap()
+                .timestamp()
+        };
+        let mut localized = String::new();
+        let mut rest = fixture;
+        while let Some(start) = rest.find(|c: char| c.is_ascii_digit()) {
+            let end = rest[start..]
+                .find(|c: char| !c.is_ascii_digit())
+                .map_or(rest.len(), |len| start + len);
+            localized.push_str(&rest[..start]);
+            match rest[start..end].parse::<i64>() {
+                Ok(ts) if ts > i64::from(MAX_PUZZLE_NUMBER) => {
+                    localized.push_str(&local(ts).to_string());
+                }
+                _ => localized.push_str(&rest[start..end]),
+            }
+            rest = &rest[end..];
+        }
+        localized.push_str(rest);
+        localized
+    }
 
     #[test]
     fn test_streak() {
-        let day1 = NaiveDate::from_ymd(1987, 11, 11).and_hms(0, 0, 0);
         let winner = |ts| SaveState {
             word: "pauls".into(),
             guesses: vec![vec![
@@ -193,6 +1030,11 @@ mod test {
                 CellValue::Correct('s'),
             ]],
             game_mode: GameMode::Daily(ts),
+            hard_mode: false,
+            max_guesses: Some(6),
+            hints_used: false,
+            started_at: None,
+            finished_at: None,
         };
         let loser = |ts| SaveState {
             word: "pauls".into(),
@@ -204,11 +1046,16 @@ mod test {
                 CellValue::Correct('p'),
             ]],
             game_mode: GameMode::Daily(ts),
+            hard_mode: false,
+            max_guesses: Some(6),
+            hints_used: false,
+            started_at: None,
+            finished_at: None,
         };
 
         let mut history = GameHistory::default();
 
-        let ts = |days| (day1 + chrono::Duration::days(days)).timestamp();
+        let ts = |days: u32| 400 + days;
 
         assert_eq!(history.current_streak(), 0);
 
@@ -237,4 +1084,190 @@ mod test {
         history.scores.insert(ts(9), winner(ts(9)));
         assert_eq!(history.current_streak(), 1);
     }
+
+    #[test]
+    fn test_migrate_saves() {
+        let state: SaveState = decode(
+            &localized(include_str!("../fixtures/save_v1_daily.json")),
+            &SAVE_SCHEMA,
+            1,
+        )
+        .unwrap();
+        assert_eq!(state.game_mode, GameMode::Daily(412));
+        assert_eq!(state.guesses.len(), 1);
+        assert!(!state.hints_used);
+
+        let state: SaveState = decode(
+            include_str!("../fixtures/save_v1_random.json"),
+            &SAVE_SCHEMA,
+            1,
+        )
+        .unwrap();
+        assert_eq!(state.game_mode, GameMode::Custom(encode_word("crane")));
+
+        let state: MultiSaveState = decode(
+            include_str!("../fixtures/multi_save_v1.json"),
+            &MULTI_SAVE_SCHEMA,
+            1,
+        )
+        .unwrap();
+        let migrated = GameMode::Custom(encode_word("craneabbeystoicbuild"));
+        assert_eq!(state.game_mode, migrated);
+        assert_eq!(state.guesses, ["slate"]);
+
+        // and once saved, a save reads back as it was
+        let raw = encode(&state, &MULTI_SAVE_SCHEMA).unwrap();
+        let state: MultiSaveState = decode(&raw, &MULTI_SAVE_SCHEMA, 1).unwrap();
+        assert_eq!(state.game_mode, migrated);
+    }
+
+    #[test]
+    fn test_migrate_history() {
+        let history: GameHistory = decode(
+            &localized(include_str!("../fixtures/history_v1.json")),
+            &HISTORY_SCHEMA,
+            1,
+        )
+        .unwrap();
+        // midnight, then an hour off either way as after a clock change, and a game
+        // from before the schedule that has no puzzle to go under
+        let mut puzzles = history.scores.keys().copied().collect::<Vec<_>>();
+        puzzles.sort_unstable();
+        assert_eq!(puzzles, [412, 413, 414]);
+        assert!(history
+            .scores
+            .iter()
+            .all(|(puzzle, state)| state.game_mode == GameMode::Daily(*puzzle)));
+        assert_eq!(history.scores[&412].word, "crane");
+
+        let history: GameHistory<MultiSaveState> = decode(
+            &localized(include_str!("../fixtures/multi_history_v1.json")),
+            &MULTI_HISTORY_SCHEMA,
+            1,
+        )
+        .unwrap();
+        assert_eq!(history.scores[&412].game_mode, GameMode::Daily(412));
+    }
+
+    #[test]
+    fn test_save_and_finish() {
+        let store = Store::new(MemoryStore::default());
+        let mut live = play(&store, GameMode::Daily(412), "crane", &["slate"]);
+        let saved = load_saved_sate(&store, PlayMode::Daily, 5).unwrap();
+        assert_eq!(saved.guesses.len(), 1);
+        assert!(load_game_history(&store, 5, Some(6)).scores.is_empty());
+
+        // finishing moves the game into the history
+        guess(&store, &mut live, &["crane"]);
+        assert!(load_saved_sate(&store, PlayMode::Daily, 5).is_none());
+        let history = load_game_history(&store, 5, Some(6));
+        assert_eq!(history.scores[&412].guesses.len(), 2);
+        assert_eq!(history.current_streak(), 1);
+
+        // a stale tab finishing the same puzzle doesn't replace the result
+        play(&store, GameMode::Daily(412), "crane", &["crane"]);
+        let history = load_game_history(&store, 5, Some(6));
+        assert_eq!(history.scores[&412].guesses.len(), 2);
+    }
+
+    #[test]
+    fn test_save_slots() {
+        let store = Store::new(MemoryStore::default());
+        store
+            .set(
+                LEGACY_SAVE_KEY,
+                include_str!("../fixtures/save_v1_random.json"),
+            )
+            .unwrap();
+        play(&store, GameMode::Daily(412), "abbey", &["slate"]);
+
+        // the old shared save went to the slot for its game, and the daily game
+        // didn't replace it
+        let random = load_saved_sate(&store, PlayMode::Custom, 5).unwrap();
+        assert_eq!(random.word, "crane");
+        assert_eq!(store.get(LEGACY_SAVE_KEY), None);
+        let daily = load_saved_sate(&store, PlayMode::Daily, 5).unwrap();
+        assert_eq!(daily.word, "abbey");
+        assert!(load_saved_sate(&store, PlayMode::Daily, 6).is_none());
+        assert!(load_saved_sate(&store, PlayMode::Random, 5).is_none());
+    }
+
+    #[test]
+    fn test_multi_save_slots() {
+        let store = Store::new(MemoryStore::default());
+        store
+            .set(
+                &format!("{}_4", MULTI_SAVE_KEY),
+                include_str!("../fixtures/multi_save_v1.json"),
+            )
+            .unwrap();
+        play_multi(
+            &store,
+            GameMode::Daily(412),
+            &["crane", "abbey"],
+            &["slate"],
+        );
+
+        // an unfinished random Dordle doesn't hide the daily one
+        play_multi(&store, GameMode::Seeded(7), &["stoic", "build"], &["slate"]);
+        let saved = load_multi_saved_state(&store, PlayMode::Daily, 2).unwrap();
+        assert_eq!(saved.game_mode, GameMode::Daily(412));
+        let saved = load_multi_saved_state(&store, PlayMode::Random, 2).unwrap();
+        assert_eq!(saved.game_mode, GameMode::Seeded(7));
+
+        // the old shared save went to the slot for its game
+        let saved = load_multi_saved_state(&store, PlayMode::Random, 4).unwrap();
+        assert_eq!(saved.guesses, ["slate"]);
+        assert_eq!(store.get(&format!("{}_4", MULTI_SAVE_KEY)), None);
+        assert!(load_multi_saved_state(&store, PlayMode::Daily, 4).is_none());
+    }
+
+    #[test]
+    fn test_history_by_mode() {
+        let store = Store::new(MemoryStore::default());
+        for (game_mode, word) in [
+            (GameMode::Daily(412), "crane"),
+            (GameMode::Seeded(0x8f3a), "abbey"),
+            (GameMode::Custom(encode_word("stoic")), "stoic"),
+            (GameMode::Custom(encode_word("build")), "build"),
+        ] {
+            play(&store, game_mode, word, &[word]);
+        }
+
+        let played = |play_mode| load_history(&store, play_mode, 5, Some(6)).scores.len();
+        assert_eq!(played(PlayMode::Daily), 1);
+        assert_eq!(played(PlayMode::Random), 1);
+        assert_eq!(played(PlayMode::Custom), 2);
+        let custom = load_history(&store, PlayMode::Custom, 5, Some(6));
+        assert_eq!(custom.current_streak(), 2);
+        assert_eq!(custom.scores[&1].word, "build");
+    }
+
+    #[test]
+    fn test_history_for_other_games() {
+        let store = Store::new(MemoryStore::default());
+        for game_mode in [
+            GameMode::Seeded(1),
+            GameMode::Seeded(2),
+            GameMode::Daily(412),
+        ] {
+            let words = ["crane", "abbey"];
+            play_multi(&store, game_mode, &words, &words);
+        }
+        let random = load_multi_random_history(&store, 2);
+        assert_eq!(random.scores.len(), 2);
+        assert_eq!(random.scores[&1].game_mode, GameMode::Seeded(2));
+        assert_eq!(load_multi_history(&store, 2).scores.len(), 1);
+
+        let mut live = AbsurdlePaudle::new(store.clone());
+        live.game = AbsurdleGame::new(["crane"]);
+        live.add_guess("slate");
+        update_absurdle_saved_state(&store, &live);
+        assert!(load_absurdle_history(&store).scores.is_empty());
+        live.add_guess("crane");
+        update_absurdle_saved_state(&store, &live);
+        let absurdle = load_absurdle_history(&store);
+        assert_eq!(absurdle.scores[&0].guess_count(), 2);
+        assert_eq!(absurdle.wins(), 1);
+    }
 }
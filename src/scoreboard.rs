@@ -3,42 +3,35 @@
     clippy::cast_sign_loss,
     clippy::cast_possible_truncation
 )]
-use std::collections::HashMap;
+use std::{collections::BTreeMap, ops::RangeInclusive};
 
+use gloo_timers::callback::Interval;
 use patternfly_yew::BackdropDispatcher;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
+use web_sys::HtmlSelectElement;
 use yew::prelude::*;
 
-use crate::{board::CellValue, save::load_game_history, GameState, PaudleMsg};
+use paudle_core::CellValue;
 
-#[derive(Properties, PartialEq)]
-pub struct ScoreboardProps {
-    pub word: String,
-    pub guesses: Vec<Vec<CellValue>>,
-    pub max_guesses: usize,
-    pub game_state: GameState,
-}
-
-#[derive(Properties, PartialEq)]
-pub struct ScoreRowProps {
-    pub guess: Vec<CellValue>,
-}
-
-#[function_component(ScoreRow)]
-pub fn score_row(props: &ScoreRowProps) -> Html {
-    html! { <>{ props.guess.iter().copied().map(CellValue::score_char).collect::<String>() }<br /></> }
-}
+use crate::{
+    save::{GameHistory, Outcome},
+    settings::ShareFormat,
+    time_to_next_puzzle, PaudleMsg,
+};
 
 #[derive(Properties, PartialEq)]
 pub struct DistributionBarProps {
     proportion: usize,
     num: usize,
     count: usize,
+    /// Marks the bar the game just played went into.
+    #[prop_or_default]
+    highlight: bool,
 }
 
 #[function_component(DistributionBar)]
 pub fn distribution_bar(props: &DistributionBarProps) -> Html {
-    html! { <div class="distribution-row"><div class="distribution-row-num">{props.num}{": "}</div><div class="distribution-row-bar"><div class="distribution-bar" style={format!("width: calc(18px + {} * 0.9%)", props.proportion)}>{props.count}</div></div></div> }
+    html! { <div class="distribution-row"><div class="distribution-row-num">{props.num}{": "}</div><div class="distribution-row-bar"><div class={classes!("distribution-bar", props.highlight.then_some("highlight"))} style={format!("width: calc(18px + {} * 0.9%)", props.proportion)}>{props.count}</div></div></div> }
 }
 
 #[derive(Properties, PartialEq)]
@@ -57,94 +50,302 @@ pub fn scoreboard_stat(props: &ScoreboardStatProps) -> Html {
     }
 }
 
+#[derive(PartialEq, Clone, Default)]
+pub struct Stats {
+    pub played: usize,
+    pub wins: usize,
+    pub current_streak: usize,
+    pub max_streak: usize,
+    /// Number of wins for each possible guess count, in order.
+    pub distribution: Vec<(usize, usize)>,
+}
+
+impl Stats {
+    pub fn from_history<T: Outcome>(
+        history: &GameHistory<T>,
+        guess_counts: RangeInclusive<usize>,
+    ) -> Self {
+        let mut distribution: BTreeMap<usize, usize> = guess_counts.map(|num| (num, 0)).collect();
+        let winning_games = history.scores.values().filter(|val| val.was_won());
+        winning_games.for_each(|val| {
+            distribution
+                .entry(val.guess_count())
+                .and_modify(|v| *v += 1);
+        });
+        Self {
+            played: history.scores.len(),
+            wins: history.wins(),
+            current_streak: history.current_streak(),
+            max_streak: history.max_streak(),
+            distribution: distribution.into_iter().collect(),
+        }
+    }
+}
+
+/// The stats for one kind of game, as a choice on the scoreboard.
+#[derive(PartialEq, Clone)]
+pub struct ModeStats {
+    pub label: String,
+    pub caption: String,
+    pub stats: Stats,
+    /// The guess count of a game just won, to highlight in the distribution.
+    pub highlight: Option<usize>,
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ScoreboardProps {
+    pub modes: Vec<ModeStats>,
+    /// The mode shown first.
+    #[prop_or_default]
+    pub selected: usize,
+}
+
 #[function_component(Scoreboard)]
-pub fn scoreboard(_props: &()) -> Html {
-    let history = load_game_history();
-    let total_games = history.scores.len();
-    let mut distribution: HashMap<usize, usize> = (1..=6).map(|num| (num, 0)).collect();
-    let winning_games = history.scores.values().filter(|val| val.was_won());
-    winning_games.for_each(|val| {
-        distribution
-            .entry(val.guesses.len())
-            .and_modify(|v| *v += 1);
-    });
-    let win_count = history.wins();
-    let max_wins = distribution.values().max().copied().unwrap();
+pub fn scoreboard(props: &ScoreboardProps) -> Html {
+    let selected = use_state(|| props.selected);
+    let mode = match props.modes.get(*selected).or_else(|| props.modes.first()) {
+        Some(mode) => mode,
+        None => return html! {},
+    };
+    let modes = if props.modes.len() > 1 {
+        html! {
+            <div class="scoreboard-modes">
+                { for props.modes.iter().enumerate().map(|(idx, mode)| {
+                    let select = {
+                        let selected = selected.clone();
+                        Callback::from(move |_: MouseEvent| selected.set(idx))
+                    };
+                    html! {
+                        <span
+                            class={classes!("scoreboard-mode", (idx == *selected).then_some("selected"))}
+                            onclick={select}
+                        >
+                            {&mode.label}
+                        </span>
+                    }
+                }) }
+            </div>
+        }
+    } else {
+        html! {}
+    };
+    let stats = &mode.stats;
+    let win_count = stats.wins;
+    let max_wins = stats
+        .distribution
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0);
     let ratio = 100. / (max_wins as f32 / win_count as f32);
     let proportion = |count: usize| ((count as f32 / win_count as f32) * ratio) as usize;
-    let mut bars = distribution.iter().map(|(num, count)| (num, html! { <DistributionBar num={*num} count={*count} proportion={proportion(*count)} /> })).collect::<Vec<_>>();
-    bars.sort_unstable_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+    let bars = stats.distribution.iter().map(|(num, count)| html! { <DistributionBar num={*num} count={*count} proportion={proportion(*count)} highlight={mode.highlight == Some(*num)} /> }).collect::<Vec<_>>();
     html! {
         <div class="scoreboard">
-            <div class="scoreboard-header">{"Statistics"}</div>
-            <ScoreboardStat stat={total_games.to_string()} caption={"Played"} />
-            <ScoreboardStat stat={(((win_count as f32 / total_games as f32) * 100.) as usize).to_string()} caption={"Win %"} />
-            <ScoreboardStat stat={history.current_streak().to_string()} caption={"Current Streak"} />
-            <ScoreboardStat stat={history.max_streak().to_string()} caption={"Max Streak"} />
+            {modes}
+            <div class="scoreboard-header">{format!("Statistics ({})", mode.caption)}</div>
+            <ScoreboardStat stat={stats.played.to_string()} caption={"Played"} />
+            <ScoreboardStat stat={(((win_count as f32 / stats.played as f32) * 100.) as usize).to_string()} caption={"Win %"} />
+            <ScoreboardStat stat={stats.current_streak.to_string()} caption={"Current Streak"} />
+            <ScoreboardStat stat={stats.max_streak.to_string()} caption={"Max Streak"} />
             <div class="scoreboard-header">{"Guess Distribution"}</div>
             <div class="scoreboard-distribution">
-                {bars.into_iter().map(|(_, b)| b).collect::<Vec<_>>()}
+                {bars}
             </div>
             </div>
     }
 }
 
-fn generate_unicode_block(guesses: &[Vec<CellValue>]) -> String {
+/// `HH:MM:SS`, stopping at zero.
+fn format_countdown(remaining: chrono::Duration) -> String {
+    let secs = remaining.num_seconds().max(0);
+    format!("{:02}:{:02}:{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// A live countdown to the next daily puzzle.
+#[function_component(NextPuzzle)]
+pub fn next_puzzle() -> Html {
+    let remaining = use_state(time_to_next_puzzle);
+    {
+        let remaining = remaining.clone();
+        use_effect_with_deps(
+            move |_| {
+                let interval = Interval::new(1000, move || remaining.set(time_to_next_puzzle()));
+                move || drop(interval)
+            },
+            (),
+        );
+    }
+    html! {
+        <div class="next-puzzle">
+            {format!("Next Paudle in {}", format_countdown(*remaining))}
+        </div>
+    }
+}
+
+pub(crate) fn score_char(cell: CellValue, format: ShareFormat) -> char {
+    match (format, cell) {
+        (ShareFormat::Dark, CellValue::Empty | CellValue::Typing(_) | CellValue::Absent(_)) => '⬛',
+        (ShareFormat::HighContrast, CellValue::Present(_)) => '🟦',
+        (ShareFormat::HighContrast, CellValue::Correct(_)) => '🟧',
+        _ => cell.score_char(),
+    }
+}
+
+/// "P correct", "A absent" and so on.
+fn describe_cell(cell: CellValue) -> String {
+    let result = match cell {
+        CellValue::Empty | CellValue::Typing(_) => "empty",
+        CellValue::Absent(_) => "absent",
+        CellValue::Present(_) => "present",
+        CellValue::Correct(_) => "correct",
+    };
+    match cell.letter() {
+        Some(c) => format!("{} {}", c.to_ascii_uppercase(), result),
+        None => result.to_string(),
+    }
+}
+
+fn generate_grid(guesses: &[Vec<CellValue>], format: ShareFormat) -> String {
     guesses
         .iter()
-        .map(|g| {
-            g.iter()
-                .copied()
-                .map(CellValue::score_char)
-                .collect::<String>()
+        .enumerate()
+        .map(|(idx, g)| match format {
+            ShareFormat::Text => format!(
+                "Row {}: {}",
+                idx + 1,
+                g.iter()
+                    .copied()
+                    .map(describe_cell)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            _ => g.iter().map(|c| score_char(*c, format)).collect::<String>(),
         })
         .collect::<Vec<String>>()
         .join("\n")
 }
 
-fn generate_score_copy(
+/// The share text in every format, for the footer to choose between.
+pub fn share_formats(text: impl Fn(ShareFormat) -> String) -> Vec<(ShareFormat, String)> {
+    ShareFormat::ALL
+        .into_iter()
+        .map(|format| (format, text(format)))
+        .collect()
+}
+
+pub fn generate_score_copy(
+    name: &str,
     won: bool,
-    max_guesses: usize,
+    max_guesses: Option<usize>,
     guesses: &[Vec<CellValue>],
     modifiers: &str,
+    format: ShareFormat,
 ) -> String {
     format!(
-        "Paudle {}/{}{}\n\n{}",
+        "{} {}/{}{}\n\n{}",
+        name,
         if won {
             guesses.len().to_string()
         } else {
             "X".to_string()
         },
+        max_guesses.map_or_else(|| "∞".to_string(), |max| max.to_string()),
+        modifiers,
+        generate_grid(guesses, format)
+    )
+}
+
+pub fn generate_absurdle_score_copy(guesses: &[Vec<CellValue>], format: ShareFormat) -> String {
+    format!(
+        "Paudle Absurdle {}\n\n{}",
+        guesses.len(),
+        generate_grid(guesses, format)
+    )
+}
+
+/// Keycap emoji for the guess each board was solved on, or a red square if it
+/// wasn't solved.
+fn solved_in_char(solved_in: Option<usize>) -> String {
+    match solved_in {
+        Some(n @ 1..=9) => format!("{}\u{fe0f}\u{20e3}", n),
+        Some(10) => "🔟".to_string(),
+        Some(11) => "🕚".to_string(),
+        Some(12) => "🕛".to_string(),
+        Some(_) => "🕐".to_string(),
+        None => "🟥".to_string(),
+    }
+}
+
+/// "Board 1: solved in 3" and so on, for [`ShareFormat::Text`].
+fn describe_board(idx: usize, solved_in: Option<usize>) -> String {
+    match solved_in {
+        Some(n) => format!("Board {}: solved in {}", idx + 1, n),
+        None => format!("Board {}: not solved", idx + 1),
+    }
+}
+
+/// The boards are drawn two to a row, whatever the colour format, since keycaps
+/// don't change with it. Only [`ShareFormat::Text`] writes them out instead.
+pub fn generate_multi_score_copy(
+    name: &str,
+    won: bool,
+    guess_count: usize,
+    max_guesses: usize,
+    solved_in: &[Option<usize>],
+    modifiers: &str,
+    format: ShareFormat,
+) -> String {
+    let boards = match format {
+        ShareFormat::Text => solved_in
+            .iter()
+            .enumerate()
+            .map(|(idx, n)| describe_board(idx, *n))
+            .collect::<Vec<_>>(),
+        _ => solved_in
+            .chunks(2)
+            .map(|row| row.iter().copied().map(solved_in_char).collect::<String>())
+            .collect::<Vec<_>>(),
+    };
+    format!(
+        "Paudle {} {}/{}{}\n\n{}",
+        name,
+        if won {
+            guess_count.to_string()
+        } else {
+            "X".to_string()
+        },
         max_guesses,
         modifiers,
-        generate_unicode_block(guesses)
+        boards.join("\n")
     )
 }
 
 #[derive(Properties, PartialEq)]
 pub struct ScoreboardFooterProps {
-    pub guesses: Vec<Vec<CellValue>>,
-    pub won: bool,
-    pub max_guesses: usize,
+    /// The share text in each format offered. With more than one the player can
+    /// choose, and the choice is sent back as [`PaudleMsg::SetShareFormat`].
+    pub share_texts: Vec<(ShareFormat, String)>,
+    /// The format picked last time.
+    #[prop_or_default]
+    pub format: ShareFormat,
     pub clear: Callback<PaudleMsg>,
-    pub random: bool,
 }
 
 #[function_component(ScoreboardFooter)]
 pub fn scoreboard_footer(props: &ScoreboardFooterProps) -> Html {
-    let guesses = props.guesses.clone();
-    let won = props.won;
-    let max_guesses = props.max_guesses;
-    let random = props.random;
+    let format = use_state(|| props.format);
+    let share_text = props
+        .share_texts
+        .iter()
+        .find(|(f, _)| *f == *format)
+        .or_else(|| props.share_texts.first())
+        .map(|(_, text)| text.clone())
+        .unwrap_or_default();
     let label = use_state(|| "Share score".to_string());
     let cblabel = label.clone();
     let cb = Callback::from(move |_: MouseEvent| {
-        let modifiers = if random {
-            "r".to_string()
-        } else {
-            String::new()
-        };
-        let boxes = generate_score_copy(won, max_guesses, &guesses, &modifiers);
+        let boxes = share_text.clone();
         wasm_bindgen_futures::spawn_local(async move {
             copy_to_clipboard(boxes).await.unwrap();
         });
@@ -155,8 +356,38 @@ pub fn scoreboard_footer(props: &ScoreboardFooterProps) -> Html {
         clear.emit(PaudleMsg::StartRandom);
         BackdropDispatcher::default().close();
     });
+    let formats = (props.share_texts.len() > 1).then(|| {
+        let choices = props
+            .share_texts
+            .iter()
+            .map(|(f, _)| *f)
+            .collect::<Vec<_>>();
+        let set_format = {
+            let format = format.clone();
+            let label = label.clone();
+            let clear = props.clear.clone();
+            let choices = choices.clone();
+            Callback::from(move |e: Event| {
+                let idx = e
+                    .target_unchecked_into::<HtmlSelectElement>()
+                    .selected_index();
+                if let Some(f) = usize::try_from(idx).ok().and_then(|idx| choices.get(idx)) {
+                    format.set(*f);
+                    label.set("Share score".to_string());
+                    clear.emit(PaudleMsg::SetShareFormat(*f));
+                }
+            })
+        };
+        html! {
+            <select class="share-format" onchange={set_format} title="Share format">
+                { for choices.iter().map(|f| html! {
+                    <option selected={*f == *format}>{f.label()}</option>
+                }) }
+            </select>
+        }
+    });
     html! {
-        <div class="share-score"><span onclick={cb}>{&*label}</span><span class="play-button" onclick={ccb}>{"Play random"}</span></div>
+        <div class="share-score">{for formats}<span onclick={cb}>{&*label}</span><span class="play-button" onclick={ccb}>{"Play random"}</span></div>
     }
 }
 
@@ -174,5 +405,62 @@ export function copy_to_clipboard(value) {
 #[rustfmt::skip] // required to keep the "async" keyword
 extern "C" { 
     #[wasm_bindgen(catch)]
-    async fn copy_to_clipboard(value: String) -> Result<(), JsValue>;
+    pub(crate) async fn copy_to_clipboard(value: String) -> Result<(), JsValue>;
+}
+
+#[cfg(test)]
+mod test {
+    use chrono::Duration;
+
+    use paudle_core::evaluate_guess;
+
+    use crate::settings::ShareFormat;
+
+    use super::{format_countdown, generate_multi_score_copy, generate_score_copy};
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(
+            format_countdown(Duration::seconds(3 * 3600 + 12 * 60 + 5)),
+            "03:12:05"
+        );
+        assert_eq!(format_countdown(Duration::seconds(59)), "00:00:59");
+        assert_eq!(format_countdown(Duration::seconds(-1)), "00:00:00");
+    }
+
+    #[test]
+    fn test_share_formats() {
+        let guesses = [
+            evaluate_guess("pauls", "plush"),
+            evaluate_guess("pauls", "pauls"),
+        ];
+        let copy = |format| generate_score_copy("Paudle #1", true, Some(6), &guesses, "", format);
+        assert_eq!(
+            copy(ShareFormat::Light),
+            "Paudle #1 2/6\n\n🟩🟨🟩🟨⬜\n🟩🟩🟩🟩🟩"
+        );
+        assert_eq!(
+            copy(ShareFormat::Dark),
+            "Paudle #1 2/6\n\n🟩🟨🟩🟨⬛\n🟩🟩🟩🟩🟩"
+        );
+        assert_eq!(
+            copy(ShareFormat::HighContrast),
+            "Paudle #1 2/6\n\n🟧🟦🟧🟦⬜\n🟧🟧🟧🟧🟧"
+        );
+        assert_eq!(
+            copy(ShareFormat::Text).lines().nth(2),
+            Some("Row 1: P correct, L present, U correct, S present, H absent")
+        );
+
+        let copy =
+            |format| generate_multi_score_copy("Dordle", false, 7, 7, &[Some(3), None], "", format);
+        assert_eq!(
+            copy(ShareFormat::Dark),
+            "Paudle Dordle X/7\n\n3\u{fe0f}\u{20e3}🟥"
+        );
+        assert_eq!(
+            copy(ShareFormat::Text),
+            "Paudle Dordle X/7\n\nBoard 1: solved in 3\nBoard 2: not solved"
+        );
+    }
 }
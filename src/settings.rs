@@ -0,0 +1,196 @@
+use paudle_core::{DEFAULT_MAX_GUESSES, DEFAULT_WORD_LENGTH, MULTI_BOARD_COUNTS, WORD_LENGTHS};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    store::LocalStore,
+    versioned::{self, Schema},
+    GUESS_LIMITS,
+};
+
+pub const SETTINGS_KEY: &str = "paudle_settings_v1";
+
+pub const SETTINGS_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameType {
+    #[default]
+    Classic,
+    /// One guess played on this many boards at once.
+    Multi(usize),
+    /// No fixed answer; the game dodges guesses for as long as it can.
+    Absurdle,
+    /// Racing other players to the same word, through `paudle-server`.
+    Versus,
+}
+
+/// The kinds of classic game. Each keeps its own save, so one can be left and
+/// picked up again later.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PlayMode {
+    #[default]
+    Daily,
+    Random,
+    Custom,
+}
+
+/// How shared results draw the board.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ShareFormat {
+    /// ⬜, 🟨 and 🟩, as the board shows.
+    #[default]
+    Light,
+    /// ⬛ for absent letters, to sit on a dark background.
+    Dark,
+    /// 🟧 and 🟦 in place of green and yellow.
+    HighContrast,
+    /// Each row written out, letter by letter, for screen readers.
+    Text,
+}
+
+impl ShareFormat {
+    pub const ALL: [ShareFormat; 4] = [
+        ShareFormat::Light,
+        ShareFormat::Dark,
+        ShareFormat::HighContrast,
+        ShareFormat::Text,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ShareFormat::Light => "Light",
+            ShareFormat::Dark => "Dark",
+            ShareFormat::HighContrast => "High contrast",
+            ShareFormat::Text => "Text",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Settings {
+    #[serde(default)]
+    pub game_type: GameType,
+    #[serde(default)]
+    pub hard_mode: bool,
+    #[serde(default = "default_word_length")]
+    pub word_length: usize,
+    #[serde(default = "default_max_guesses")]
+    pub max_guesses: Option<usize>,
+    /// Hints and the remaining word count are only offered for daily games if the
+    /// player asks for them.
+    #[serde(default)]
+    pub daily_hints: bool,
+    /// The kind of classic game last played.
+    #[serde(default)]
+    pub play_mode: PlayMode,
+    /// The server daily histories are synced with, if the player has set one up.
+    #[serde(default)]
+    pub sync: Option<SyncSettings>,
+    #[serde(default)]
+    pub share_format: ShareFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SyncSettings {
+    /// Where `paudle-server` is running, such as `http://localhost:8787`.
+    pub url: String,
+    /// Every device using the same profile shares one history.
+    pub profile: String,
+    /// The group daily results are posted to, for its leaderboard.
+    #[serde(default)]
+    pub group: Option<String>,
+    /// Proves to the server that requests for `profile` come from one of its
+    /// devices. The first key a profile syncs with is the only one it accepts
+    /// afterwards.
+    #[serde(default)]
+    pub key: String,
+}
+
+fn default_word_length() -> usize {
+    DEFAULT_WORD_LENGTH
+}
+
+pub fn default_max_guesses() -> Option<usize> {
+    Some(DEFAULT_MAX_GUESSES)
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            game_type: GameType::default(),
+            hard_mode: false,
+            word_length: DEFAULT_WORD_LENGTH,
+            max_guesses: default_max_guesses(),
+            daily_hints: false,
+            play_mode: PlayMode::default(),
+            sync: None,
+            share_format: ShareFormat::default(),
+        }
+    }
+}
+
+/// Settings always live in LocalStorage so they can be read before the save store
+/// has opened.
+pub fn update_settings(settings: &Settings) {
+    versioned::store(&LocalStore, SETTINGS_KEY, settings, &SETTINGS_SCHEMA);
+}
+
+pub fn load_settings() -> Settings {
+    versioned::load::<Settings>(&LocalStore, SETTINGS_KEY, &SETTINGS_SCHEMA, 1)
+        .unwrap_or_default()
+        .in_range()
+}
+
+impl Settings {
+    /// Pulls anything the game doesn't offer back to the nearest thing it does.
+    /// Settings come from storage, where an old version or a hand edit could have
+    /// left a word length with no words.
+    fn in_range(self) -> Self {
+        let game_type = match self.game_type {
+            GameType::Multi(boards) => GameType::Multi(
+                MULTI_BOARD_COUNTS
+                    .into_iter()
+                    .min_by_key(|count| count.abs_diff(boards))
+                    .expect("there are board counts"),
+            ),
+            other => other,
+        };
+        Self {
+            game_type,
+            word_length: self
+                .word_length
+                .clamp(*WORD_LENGTHS.start(), *WORD_LENGTHS.end()),
+            max_guesses: self
+                .max_guesses
+                .map(|max| max.clamp(*GUESS_LIMITS.start(), *GUESS_LIMITS.end())),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GameType, Settings};
+
+    #[test]
+    fn test_settings_in_range() {
+        let stored = Settings {
+            game_type: GameType::Multi(3),
+            word_length: 9,
+            max_guesses: Some(0),
+            ..Settings::default()
+        };
+        let settings = stored.in_range();
+        assert_eq!(settings.game_type, GameType::Multi(2));
+        assert_eq!(settings.word_length, 8);
+        assert_eq!(settings.max_guesses, Some(3));
+        assert_eq!(Settings::default().in_range(), Settings::default());
+        let unlimited = Settings {
+            max_guesses: None,
+            ..Settings::default()
+        };
+        assert_eq!(unlimited.clone().in_range(), unlimited);
+    }
+}
@@ -0,0 +1,336 @@
+//! Where saved games and histories live. Everything in `save.rs` goes through a
+//! [`SaveStore`], so the same code can run against the browser's storage or, in
+//! tests, against memory.
+//!
+//! The store is synchronous because components save from inside `update`. The
+//! IndexedDB store gets there by reading the whole database into memory when it's
+//! opened and writing changes back in the background.
+//!
+//! Other tabs write to the same storage. Only LocalStorage tells us about that, with
+//! the `storage` event, so the IndexedDB store leaves a note in LocalStorage after
+//! every write for other tabs to pick up.
+use std::{cell::RefCell, collections::HashMap, rc::Rc};
+
+use gloo_events::EventListener;
+use gloo_storage::{LocalStorage, Storage};
+use js_sys::{Array, Date, Promise};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    console, window, IdbDatabase, IdbObjectStore, IdbRequest, IdbTransactionMode, StorageEvent,
+};
+use yew::Callback;
+
+pub trait SaveStore {
+    fn get(&self, key: &str) -> Option<String>;
+    fn set(&self, key: &str, value: &str) -> Result<(), String>;
+    fn remove(&self, key: &str);
+
+    /// Calls `on_change` with the key of anything another tab changes, once the new
+    /// value can be read from this store. Stops when the listener is dropped. Stores
+    /// no other tab can see have nothing to watch.
+    fn watch(&self, _on_change: Callback<String>) -> Option<EventListener> {
+        None
+    }
+}
+
+/// A store shared between the components that save through it. Compares by
+/// identity so it can be passed as a prop.
+#[derive(Clone)]
+pub struct Store(Rc<dyn SaveStore>);
+
+impl Store {
+    pub fn new(store: impl SaveStore + 'static) -> Self {
+        Self(Rc::new(store))
+    }
+}
+
+impl SaveStore for Store {
+    fn get(&self, key: &str) -> Option<String> {
+        self.0.get(key)
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.0.set(key, value)
+    }
+
+    fn remove(&self, key: &str) {
+        self.0.remove(key);
+    }
+
+    fn watch(&self, on_change: Callback<String>) -> Option<EventListener> {
+        self.0.watch(on_change)
+    }
+}
+
+impl PartialEq for Store {
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+pub struct LocalStore;
+
+impl SaveStore for LocalStore {
+    fn get(&self, key: &str) -> Option<String> {
+        LocalStorage::raw().get_item(key).ok().flatten()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        LocalStorage::raw()
+            .set_item(key, value)
+            .map_err(|e| format!("{:?}", e))
+    }
+
+    fn remove(&self, key: &str) {
+        LocalStorage::delete(key);
+    }
+
+    fn watch(&self, on_change: Callback<String>) -> Option<EventListener> {
+        listen_for_storage(move |e| {
+            if let Some(key) = e.key() {
+                on_change.emit(key);
+            }
+        })
+    }
+}
+
+/// Listens for the `storage` event, which fires when another tab changes
+/// LocalStorage.
+fn listen_for_storage(on_event: impl Fn(&StorageEvent) + 'static) -> Option<EventListener> {
+    let window = window()?;
+    Some(EventListener::new(&window, "storage", move |e| {
+        if let Some(e) = e.dyn_ref::<StorageEvent>() {
+            on_event(e);
+        }
+    }))
+}
+
+#[derive(Default)]
+pub struct MemoryStore {
+    items: RefCell<HashMap<String, String>>,
+}
+
+impl SaveStore for MemoryStore {
+    fn get(&self, key: &str) -> Option<String> {
+        self.items.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.items
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        self.items.borrow_mut().remove(key);
+    }
+}
+
+const DB_NAME: &str = "paudle";
+const DB_VERSION: u32 = 1;
+const OBJECT_STORE: &str = "saves";
+
+/// Keys that belong to us when moving data over from LocalStorage.
+const KEY_PREFIX: &str = "paudle_";
+
+/// The LocalStorage note telling other tabs which key was just written to IndexedDB,
+/// as `key time`. The time makes every note different so each one fires an event.
+const CHANGE_KEY: &str = "paudle_changed_v1";
+
+/// Keeps everything in IndexedDB, which has room for histories that would outgrow
+/// LocalStorage.
+pub struct IndexedDbStore {
+    db: IdbDatabase,
+    cache: Rc<RefCell<HashMap<String, String>>>,
+}
+
+impl IndexedDbStore {
+    /// Opens the database and reads it into memory. The first time, everything we
+    /// had in LocalStorage is copied over; the originals are left where they were.
+    pub async fn open() -> Result<Self, String> {
+        let factory = window()
+            .and_then(|w| w.indexed_db().ok().flatten())
+            .ok_or("IndexedDB isn't available")?;
+        let request = factory
+            .open_with_u32(DB_NAME, DB_VERSION)
+            .map_err(js_error)?;
+        let upgrade = Closure::once_into_js(|e: web_sys::Event| {
+            let db = e
+                .target()
+                .and_then(|t| t.dyn_into::<IdbRequest>().ok())
+                .and_then(|r| r.result().ok())
+                .and_then(|db| db.dyn_into::<IdbDatabase>().ok());
+            if let Some(db) = db {
+                let _ = db.create_object_store(OBJECT_STORE);
+            }
+        });
+        request.set_onupgradeneeded(Some(upgrade.unchecked_ref()));
+        let db: IdbDatabase = wait_for(&request).await?.unchecked_into();
+
+        let store = Self {
+            db,
+            cache: Rc::default(),
+        };
+        let saves = object_store(&store.db, IdbTransactionMode::Readonly)?;
+        let keys = Array::from(&wait_for(&saves.get_all_keys().map_err(js_error)?).await?);
+        let values = Array::from(&wait_for(&saves.get_all().map_err(js_error)?).await?);
+        let cache = keys
+            .iter()
+            .zip(values.iter())
+            .filter_map(|(key, value)| Some((key.as_string()?, value.as_string()?)))
+            .collect::<HashMap<_, _>>();
+        let first_open = cache.is_empty();
+        *store.cache.borrow_mut() = cache;
+        if first_open {
+            store.import_local_storage()?;
+        }
+        Ok(store)
+    }
+
+    fn import_local_storage(&self) -> Result<(), String> {
+        let local = LocalStorage::raw();
+        let len = local.length().map_err(js_error)?;
+        let keys = (0..len)
+            .filter_map(|idx| local.key(idx).ok().flatten())
+            .filter(|key| key.starts_with(KEY_PREFIX) && key != CHANGE_KEY)
+            .collect::<Vec<_>>();
+        for key in keys {
+            if let Some(value) = LocalStore.get(&key) {
+                self.set(&key, &value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Runs `write` against the database, and tells other tabs about `key` once
+    /// it has been committed. The write finishes after we've returned, so if it
+    /// fails all we can do is log it.
+    fn write(
+        &self,
+        key: &str,
+        write: impl FnOnce(&IdbObjectStore) -> Result<IdbRequest, JsValue>,
+    ) -> Result<(), String> {
+        let tx = self
+            .db
+            .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)
+            .map_err(js_error)?;
+        write(&tx.object_store(OBJECT_STORE).map_err(js_error)?).map_err(js_error)?;
+        let note = format!("{} {}", key, Date::now());
+        tx.set_oncomplete(Some(
+            Closure::once_into_js(move || {
+                let _ = LocalStore.set(CHANGE_KEY, &note);
+            })
+            .unchecked_ref(),
+        ));
+        let log_failure = |what: &'static str| {
+            let tx = tx.clone();
+            let key = key.to_string();
+            Closure::once_into_js(move || {
+                let reason = tx
+                    .error()
+                    .map(|e| e.message())
+                    .unwrap_or_else(|| "no reason given".to_string());
+                console::log_1(&format!("Writing {} {}: {}", key, what, reason).into());
+            })
+        };
+        tx.set_onerror(Some(log_failure("failed").unchecked_ref()));
+        tx.set_onabort(Some(log_failure("was aborted").unchecked_ref()));
+        Ok(())
+    }
+}
+
+impl SaveStore for IndexedDbStore {
+    fn get(&self, key: &str) -> Option<String> {
+        self.cache.borrow().get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) -> Result<(), String> {
+        self.write(key, |saves| {
+            saves.put_with_key(&JsValue::from_str(value), &JsValue::from_str(key))
+        })?;
+        self.cache
+            .borrow_mut()
+            .insert(key.to_string(), value.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = self.write(key, |saves| saves.delete(&JsValue::from_str(key)));
+        self.cache.borrow_mut().remove(key);
+    }
+
+    fn watch(&self, on_change: Callback<String>) -> Option<EventListener> {
+        let db = self.db.clone();
+        let cache = Rc::clone(&self.cache);
+        listen_for_storage(move |e| {
+            if e.key().as_deref() != Some(CHANGE_KEY) {
+                return;
+            }
+            let key = match e
+                .new_value()
+                .and_then(|note| note.rsplit_once(' ').map(|(key, _)| key.to_string()))
+            {
+                Some(key) => key,
+                None => return,
+            };
+            let (db, cache, on_change) = (db.clone(), Rc::clone(&cache), on_change.clone());
+            spawn_local(async move {
+                match read(&db, &key).await {
+                    Ok(Some(value)) => {
+                        cache.borrow_mut().insert(key.clone(), value);
+                    }
+                    Ok(None) => {
+                        cache.borrow_mut().remove(&key);
+                    }
+                    Err(e) => {
+                        console::log_1(&format!("Couldn't reload {}: {}", key, e).into());
+                        return;
+                    }
+                }
+                on_change.emit(key);
+            });
+        })
+    }
+}
+
+fn object_store(db: &IdbDatabase, mode: IdbTransactionMode) -> Result<IdbObjectStore, String> {
+    db.transaction_with_str_and_mode(OBJECT_STORE, mode)
+        .and_then(|tx| tx.object_store(OBJECT_STORE))
+        .map_err(js_error)
+}
+
+async fn read(db: &IdbDatabase, key: &str) -> Result<Option<String>, String> {
+    let request = object_store(db, IdbTransactionMode::Readonly)?
+        .get(&JsValue::from_str(key))
+        .map_err(js_error)?;
+    Ok(wait_for(&request).await?.as_string())
+}
+
+/// The result of an IndexedDB request, once it has one.
+async fn wait_for(request: &IdbRequest) -> Result<JsValue, String> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_success = request.clone();
+        request.set_onsuccess(Some(
+            Closure::once_into_js(move || {
+                let _ = resolve.call1(&JsValue::NULL, &on_success.result().unwrap_or_default());
+            })
+            .unchecked_ref(),
+        ));
+        request.set_onerror(Some(
+            Closure::once_into_js(move || {
+                let _ = reject.call0(&JsValue::NULL);
+            })
+            .unchecked_ref(),
+        ));
+    });
+    JsFuture::from(promise)
+        .await
+        .map_err(|_| format!("{:?}", request.error()))
+}
+
+#[allow(clippy::needless_pass_by_value)]
+pub(crate) fn js_error(e: JsValue) -> String {
+    format!("{:?}", e)
+}
@@ -0,0 +1,385 @@
+//! Keeps daily histories in step with a `paudle-server`, so the same stats show up
+//! on every device. Random and custom histories stay put: they're numbered in the
+//! order each device played them, so their numbers don't line up between devices.
+use std::fmt;
+
+use paudle_core::sync::{SyncedGame, SyncedHistories, SyncedHistory};
+use rand::{thread_rng, Rng};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{window, Headers, HtmlInputElement, Request, RequestInit, Response};
+use yew::prelude::*;
+
+use crate::{
+    save::{DailyHistory, GameHistory},
+    settings::SyncSettings,
+    store::{js_error, Store},
+    versioned,
+};
+
+/// Sends every daily history to the server and keeps what comes back, which the
+/// server has merged with what other devices sent. Games that couldn't have been
+/// played here are dropped on the way in. Returns whether anything here changed.
+pub async fn sync(store: &Store, settings: &SyncSettings) -> Result<bool, String> {
+    let histories = DailyHistory::all();
+    let local = histories
+        .iter()
+        .filter_map(|history| {
+            let key = history.key();
+            let saved = versioned::load::<GameHistory<Value>>(store, &key, history.schema(), 2)?;
+            Some((key, to_synced(saved)))
+        })
+        .collect::<SyncedHistories<Value>>();
+    let url = format!(
+        "{}/profiles/{}/histories",
+        settings.url.trim_end_matches('/'),
+        js_sys::encode_uri_component(&settings.profile)
+    );
+    let merged: SyncedHistories<Value> = post_json(&url, &local, &settings.key)
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut changed = false;
+    for history in histories {
+        let key = history.key();
+        let Some(synced) = merged.get(&key) else {
+            continue;
+        };
+        let kept = accepted(history, synced);
+        if local.get(&key) != Some(&kept) {
+            let kept = GameHistory {
+                scores: kept
+                    .into_iter()
+                    .map(|(puzzle, synced)| (puzzle, synced.game))
+                    .collect(),
+            };
+            versioned::store(store, &key, &kept, history.schema());
+            changed = true;
+        }
+    }
+    Ok(changed)
+}
+
+/// The games in `synced` that belong in `history`.
+fn accepted(history: DailyHistory, synced: &SyncedHistory<Value>) -> SyncedHistory<Value> {
+    synced
+        .iter()
+        .filter(|(puzzle, synced)| history.accepts(**puzzle, &synced.game))
+        .map(|(puzzle, synced)| (*puzzle, synced.clone()))
+        .collect()
+}
+
+/// Games are dated by when they were finished. Older saves didn't record that, so
+/// they lose to any copy that did.
+fn to_synced(history: GameHistory<Value>) -> SyncedHistory<Value> {
+    history
+        .scores
+        .into_iter()
+        .map(|(puzzle, game)| {
+            let updated_at = game.get("finished_at").and_then(Value::as_i64).unwrap_or(0);
+            (puzzle, SyncedGame { updated_at, game })
+        })
+        .collect()
+}
+
+/// Why a request to the server didn't work out.
+#[derive(Debug)]
+pub(crate) enum ServerError {
+    /// The server answered with this status.
+    Status(u16),
+    /// There was no answer, or it wasn't understood.
+    Failed(String),
+}
+
+impl fmt::Display for ServerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ServerError::Status(status) => write!(f, "the server answered {}", status),
+            ServerError::Failed(e) => f.write_str(e),
+        }
+    }
+}
+
+impl From<String> for ServerError {
+    fn from(e: String) -> Self {
+        ServerError::Failed(e)
+    }
+}
+
+/// Posts `body` as JSON on behalf of the profile with `key`.
+pub(crate) async fn post_json<T: Serialize, R: DeserializeOwned>(
+    url: &str,
+    body: &T,
+    key: &str,
+) -> Result<R, ServerError> {
+    let body = serde_json::to_string(body).map_err(|e| e.to_string())?;
+    request_json("POST", url, Some(&body), key).await
+}
+
+/// Fetches JSON on behalf of the profile with `key`.
+pub(crate) async fn get_json<R: DeserializeOwned>(url: &str, key: &str) -> Result<R, ServerError> {
+    request_json("GET", url, None, key).await
+}
+
+async fn request_json<R: DeserializeOwned>(
+    method: &str,
+    url: &str,
+    body: Option<&str>,
+    key: &str,
+) -> Result<R, ServerError> {
+    // Sync set up before profiles had keys
+    if key.is_empty() {
+        return Err("save the sync settings again to give this profile a key"
+            .to_string()
+            .into());
+    }
+    let headers = Headers::new().map_err(js_error)?;
+    headers
+        .set("Content-Type", "application/json")
+        .map_err(js_error)?;
+    headers
+        .set("Authorization", &format!("Bearer {}", key))
+        .map_err(js_error)?;
+    let init = RequestInit::new();
+    init.set_method(method);
+    init.set_headers(&headers);
+    if let Some(body) = body {
+        init.set_body(&JsValue::from_str(body));
+    }
+    let request = Request::new_with_str_and_init(url, &init).map_err(js_error)?;
+    let window = window().ok_or_else(|| "no window".to_string())?;
+    let response = JsFuture::from(window.fetch_with_request(&request))
+        .await
+        .map_err(js_error)?
+        .unchecked_into::<Response>();
+    if !response.ok() {
+        return Err(ServerError::Status(response.status()));
+    }
+    let text = JsFuture::from(response.text().map_err(js_error)?)
+        .await
+        .map_err(js_error)?;
+    serde_json::from_str(&text.as_string().unwrap_or_default())
+        .map_err(|e| ServerError::Failed(e.to_string()))
+}
+
+fn new_key() -> String {
+    format!("{:032x}", thread_rng().gen::<u128>())
+}
+
+#[derive(Properties, PartialEq)]
+pub struct SyncFormProps {
+    pub settings: Option<SyncSettings>,
+    pub on_change: Callback<Option<SyncSettings>>,
+}
+
+/// Sets up the sync server, or turns syncing off.
+#[function_component(SyncForm)]
+pub fn sync_form(props: &SyncFormProps) -> Html {
+    let url = use_state(|| {
+        props
+            .settings
+            .as_ref()
+            .map(|s| s.url.clone())
+            .unwrap_or_default()
+    });
+    let profile = use_state(|| {
+        props
+            .settings
+            .as_ref()
+            .map(|s| s.profile.clone())
+            .unwrap_or_default()
+    });
+    let group = use_state(|| {
+        props
+            .settings
+            .as_ref()
+            .and_then(|s| s.group.clone())
+            .unwrap_or_default()
+    });
+    // A new key for a new profile. Using the profile on another device means
+    // copying the key over too.
+    let key = use_state(|| {
+        props
+            .settings
+            .as_ref()
+            .map(|s| s.key.clone())
+            .filter(|k| !k.is_empty())
+            .unwrap_or_else(new_key)
+    });
+    let error = use_state(|| None::<String>);
+
+    let set_url = {
+        let url = url.clone();
+        Callback::from(move |e: InputEvent| {
+            url.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let set_profile = {
+        let profile = profile.clone();
+        Callback::from(move |e: InputEvent| {
+            profile.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let set_group = {
+        let group = group.clone();
+        Callback::from(move |e: InputEvent| {
+            group.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let set_key = {
+        let key = key.clone();
+        Callback::from(move |e: InputEvent| {
+            key.set(e.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+    let save = {
+        let url = url.clone();
+        let profile = profile.clone();
+        let group = group.clone();
+        let key = key.clone();
+        let error = error.clone();
+        let on_change = props.on_change.clone();
+        Callback::from(move |_: MouseEvent| {
+            let (url, profile, group, key) = (url.trim(), profile.trim(), group.trim(), key.trim());
+            if !(url.starts_with("http://") || url.starts_with("https://")) {
+                error.set(Some(
+                    "The server needs an http:// or https:// address".into(),
+                ));
+            } else if profile.is_empty() {
+                error.set(Some("Choose a profile name".into()));
+            } else if key.is_empty() {
+                error.set(Some("The profile needs a key".into()));
+            } else {
+                on_change.emit(Some(SyncSettings {
+                    url: url.to_string(),
+                    profile: profile.to_string(),
+                    group: (!group.is_empty()).then(|| group.to_string()),
+                    key: key.to_string(),
+                }));
+            }
+        })
+    };
+    let turn_off = props.on_change.reform(|_: MouseEvent| None);
+
+    html! {
+        <div class="sync-form">
+            <input
+                type="url"
+                placeholder="Server, e.g. http://localhost:8787"
+                value={(*url).clone()}
+                oninput={set_url}
+            />
+            <input
+                type="text"
+                placeholder="Profile"
+                value={(*profile).clone()}
+                oninput={set_profile}
+            />
+            <input
+                type="text"
+                placeholder="Key"
+                title="Enter the same key on every device using this profile"
+                value={(*key).clone()}
+                oninput={set_key}
+            />
+            <input
+                type="text"
+                placeholder="Group (optional)"
+                value={(*group).clone()}
+                oninput={set_group}
+            />
+            <span class="play-button" onclick={save}>{"Sync"}</span>
+            if props.settings.is_some() {
+                <span class="play-button" onclick={turn_off}>{"Turn off"}</span>
+            }
+            if let Some(e) = &*error {
+                <div class="puzzle-error">{e}</div>
+            }
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashMap;
+
+    use serde_json::Value;
+
+    use paudle_core::{evaluate_guess, sync::SyncedGame};
+    use serde_json::json;
+
+    use crate::{
+        save::{DailyHistory, GameHistory, MultiSaveState},
+        GameMode,
+    };
+
+    use super::{accepted, to_synced};
+
+    #[test]
+    fn test_multi_games_sync_by_finish_time() {
+        let game = MultiSaveState {
+            words: vec!["crane".into(), "abbey".into()],
+            guesses: vec!["crane".into(), "abbey".into()],
+            game_mode: GameMode::Daily(412),
+            finished_at: Some(1_700_000_000),
+        };
+        let history = GameHistory {
+            scores: HashMap::from([(412, serde_json::to_value(game).unwrap())]),
+        };
+        let synced = to_synced(history);
+        assert_eq!(synced[&412].updated_at, 1_700_000_000);
+        assert_eq!(
+            synced[&412].game["game_mode"],
+            serde_json::json!({ "Daily": 412 })
+        );
+        let untimed = GameHistory {
+            scores: HashMap::from([(1, Value::Null)]),
+        };
+        assert_eq!(to_synced(untimed)[&1].updated_at, 0);
+    }
+
+    #[test]
+    fn test_unplayable_games_are_dropped() {
+        let classic = |guesses: &[&str]| {
+            json!({
+                "word": "crane",
+                "guesses": guesses.iter().map(|g| evaluate_guess("crane", g)).collect::<Vec<_>>(),
+                "game_mode": { "Daily": 412 },
+            })
+        };
+        let synced = [
+            (412, classic(&["slate", "crane"])),
+            (413, classic(&["slate", "crane"])),
+            (414, classic(&[])),
+            (415, classic(&["slate"])),
+            (416, json!("not a game")),
+        ]
+        .into_iter()
+        .map(|(puzzle, game)| {
+            (
+                puzzle,
+                SyncedGame {
+                    updated_at: 1,
+                    game,
+                },
+            )
+        })
+        .collect();
+        let history = DailyHistory::Classic {
+            word_length: 5,
+            max_guesses: Some(6),
+        };
+        // only the finished game filed under its own puzzle is kept
+        assert_eq!(
+            accepted(history, &synced).into_keys().collect::<Vec<_>>(),
+            [412]
+        );
+        let elsewhere = DailyHistory::Classic {
+            word_length: 6,
+            max_guesses: Some(6),
+        };
+        assert!(accepted(elsewhere, &synced).is_empty());
+        assert!(accepted(DailyHistory::Multi { boards: 2 }, &synced).is_empty());
+    }
+}
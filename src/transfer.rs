@@ -0,0 +1,425 @@
+//! Moving history between browsers. An export is every finished classic and
+//! multi-board game, as JSON that can be imported again or as CSV for spreadsheets.
+//! Absurdle histories stay where they are. Imports are merged into the history
+//! already here; nothing recorded is ever overwritten.
+use chrono::TimeZone;
+use js_sys::Array;
+use patternfly_yew::BackdropDispatcher;
+use paudle_core::{puzzle_date, MULTI_BOARD_COUNTS, WORD_LENGTHS};
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{window, Blob, BlobPropertyBag, HtmlAnchorElement, HtmlInputElement, Url};
+use yew::prelude::*;
+
+use crate::{
+    links::seed_tag,
+    multi::multi_name,
+    save::{
+        load_history, load_multi_history, load_multi_random_history, merge_into_history,
+        merge_into_multi_history, Merged, MultiSaveState, Outcome, SaveState,
+    },
+    settings::PlayMode,
+    store::{SaveStore, Store},
+    toast_error, toast_info,
+    versioned::{self, Schema},
+    GameMode, GUESS_LIMITS,
+};
+
+pub const EXPORT_SCHEMA: Schema = Schema {
+    version: 1,
+    migrations: &[],
+};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Export {
+    games: Vec<SaveState>,
+    /// Missing from exports made before multi-board games were included.
+    #[serde(default)]
+    multi_games: Vec<MultiSaveState>,
+}
+
+/// How an import went.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportReport {
+    pub added: usize,
+    pub duplicates: usize,
+    /// Games that were already recorded with different guesses, and were left out.
+    pub conflicts: Vec<String>,
+    /// Games that were never finished, or that no game here could have produced,
+    /// which don't belong in a history.
+    pub skipped: usize,
+}
+
+impl ImportReport {
+    fn count(&mut self, merged: Merged, label: String) {
+        match merged {
+            Merged::Added => self.added += 1,
+            Merged::Duplicate => self.duplicates += 1,
+            Merged::Conflict => self.conflicts.push(label),
+        }
+    }
+
+    pub fn summary(&self) -> String {
+        let mut summary = format!("Imported {} games", self.added);
+        if self.duplicates > 0 {
+            summary.push_str(&format!(", {} were already here", self.duplicates));
+        }
+        if self.skipped > 0 {
+            summary.push_str(&format!(
+                ", {} unfinished or unreadable were skipped",
+                self.skipped
+            ));
+        }
+        if !self.conflicts.is_empty() {
+            summary.push_str(&format!(
+                ". Kept the results already here for {}",
+                self.conflicts.join(", ")
+            ));
+        }
+        summary
+    }
+}
+
+/// Every finished classic game, each history in the order it was played.
+fn all_games(store: &dyn SaveStore) -> Vec<SaveState> {
+    let mut games = Vec::new();
+    for play_mode in [PlayMode::Daily, PlayMode::Random, PlayMode::Custom] {
+        for word_length in WORD_LENGTHS {
+            for max_guesses in GUESS_LIMITS.map(Some).chain([None]) {
+                let history = load_history(store, play_mode, word_length, max_guesses);
+                let mut scores = history.scores.into_iter().collect::<Vec<_>>();
+                scores.sort_unstable_by_key(|(number, _)| *number);
+                games.extend(scores.into_iter().map(|(_, game)| game));
+            }
+        }
+    }
+    games
+}
+
+/// Every finished multi-board game, each history in the order it was played.
+fn all_multi_games(store: &dyn SaveStore) -> Vec<MultiSaveState> {
+    let mut games = Vec::new();
+    for boards in MULTI_BOARD_COUNTS {
+        for history in [
+            load_multi_history(store, boards),
+            load_multi_random_history(store, boards),
+        ] {
+            let mut scores = history.scores.into_iter().collect::<Vec<_>>();
+            scores.sort_unstable_by_key(|(number, _)| *number);
+            games.extend(scores.into_iter().map(|(_, game)| game));
+        }
+    }
+    games
+}
+
+pub fn export_json(store: &dyn SaveStore) -> Result<String, String> {
+    versioned::encode(
+        &Export {
+            games: all_games(store),
+            multi_games: all_multi_games(store),
+        },
+        &EXPORT_SCHEMA,
+    )
+}
+
+/// One row per game, with a multi-board game's words separated by spaces. Words
+/// are lowercase letters, even imported ones (see [`SaveState::is_recordable`]), so
+/// no field can contain a comma or quote that needs escaping.
+pub fn export_csv(store: &dyn SaveStore) -> String {
+    let mut csv = "date,mode,word,guesses,result\n".to_string();
+    for game in all_games(store) {
+        let label = game_label(&game.game_mode);
+        csv.push_str(&csv_row(
+            &game.game_mode,
+            game.finished_at,
+            &label,
+            &game.word,
+            &game,
+        ));
+    }
+    for game in all_multi_games(store) {
+        let words = game.words.join(" ");
+        let label = multi_game_label(&game);
+        csv.push_str(&csv_row(
+            &game.game_mode,
+            game.finished_at,
+            &label,
+            &words,
+            &game,
+        ));
+    }
+    csv
+}
+
+fn csv_row(
+    game_mode: &GameMode,
+    finished_at: Option<i64>,
+    label: &str,
+    words: &str,
+    outcome: &dyn Outcome,
+) -> String {
+    let date = match game_mode {
+        GameMode::Daily(puzzle) => Some(puzzle_date(*puzzle)),
+        GameMode::Seeded(_) | GameMode::Custom(_) => finished_at
+            .and_then(|ts| chrono::Local.timestamp_opt(ts, 0).single())
+            .map(|time| time.date_naive()),
+    };
+    format!(
+        "{},{},{},{},{}\n",
+        date.map(|d| d.to_string()).unwrap_or_default(),
+        label,
+        words,
+        outcome.guess_count(),
+        if outcome.was_won() { "won" } else { "lost" },
+    )
+}
+
+fn game_label(game_mode: &GameMode) -> String {
+    match game_mode {
+        GameMode::Daily(puzzle) => format!("Daily #{}", puzzle),
+        GameMode::Seeded(seed) => format!("Random {}", seed_tag(*seed)),
+        GameMode::Custom(_) => "Custom".to_string(),
+    }
+}
+
+fn multi_game_label(game: &MultiSaveState) -> String {
+    let name = multi_name(game.words.len());
+    match game.game_mode {
+        GameMode::Daily(puzzle) => format!("{} #{}", name, puzzle),
+        GameMode::Seeded(seed) => format!("Random {} {}", name, seed_tag(seed)),
+        GameMode::Custom(_) => format!("Random {}", name),
+    }
+}
+
+pub fn import_json(store: &dyn SaveStore, raw: &str) -> Result<ImportReport, String> {
+    let export: Export = versioned::decode(raw, &EXPORT_SCHEMA, 1)?;
+    let mut report = ImportReport::default();
+    for game in export.games {
+        if !game.is_recordable() {
+            report.skipped += 1;
+            continue;
+        }
+        let label = game_label(&game.game_mode);
+        report.count(merge_into_history(store, game), label);
+    }
+    for game in export.multi_games {
+        if !game.is_recordable() {
+            report.skipped += 1;
+            continue;
+        }
+        let label = multi_game_label(&game);
+        report.count(merge_into_multi_history(store, game), label);
+    }
+    Ok(report)
+}
+
+/// Hands `contents` to the browser as a file to save.
+fn download(file_name: &str, mime: &str, contents: &str) -> Result<(), JsValue> {
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_str_sequence_and_options(&Array::of1(&contents.into()), &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let link = window()
+        .and_then(|w| w.document())
+        .ok_or("no document")?
+        .create_element("a")?
+        .unchecked_into::<HtmlAnchorElement>();
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+    Url::revoke_object_url(&url)
+}
+
+#[derive(Properties, PartialEq)]
+pub struct HistoryTransferProps {
+    pub store: Store,
+}
+
+#[function_component(HistoryTransfer)]
+pub fn history_transfer(props: &HistoryTransferProps) -> Html {
+    let export_json = {
+        let store = props.store.clone();
+        Callback::from(move |_: MouseEvent| {
+            let downloaded = export_json(&store).and_then(|json| {
+                download("paudle-history.json", "application/json", &json)
+                    .map_err(|e| format!("{:?}", e))
+            });
+            if let Err(e) = downloaded {
+                toast_error(format!("Couldn't export: {}", e));
+            }
+        })
+    };
+    let export_csv = {
+        let store = props.store.clone();
+        Callback::from(move |_: MouseEvent| {
+            if let Err(e) = download("paudle-history.csv", "text/csv", &export_csv(&store)) {
+                toast_error(format!("Couldn't export: {:?}", e));
+            }
+        })
+    };
+    let import = {
+        let store = props.store.clone();
+        Callback::from(move |e: Event| {
+            let input = e.target_unchecked_into::<HtmlInputElement>();
+            let file = match input.files().and_then(|files| files.get(0)) {
+                Some(file) => file,
+                None => return,
+            };
+            let store = store.clone();
+            spawn_local(async move {
+                let imported = JsFuture::from(file.text())
+                    .await
+                    .ok()
+                    .and_then(|text| text.as_string())
+                    .ok_or_else(|| "the file couldn't be read".to_string())
+                    .and_then(|text| import_json(&store, &text));
+                match imported {
+                    Ok(report) => {
+                        // The stats on show are out of date now
+                        BackdropDispatcher::default().close();
+                        toast_info(report.summary());
+                    }
+                    Err(e) => toast_error(format!("Couldn't import: {}", e)),
+                }
+            });
+        })
+    };
+    html! {
+        <div class="history-transfer">
+            <span class="history-transfer-button" onclick={export_json}>{"Export JSON"}</span>
+            <span class="history-transfer-button" onclick={export_csv}>{"Export CSV"}</span>
+            <label class="history-transfer-button">
+                {"Import JSON"}
+                <input type="file" accept=".json,application/json" hidden=true onchange={import} />
+            </label>
+        </div>
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use paudle_core::puzzle_date;
+
+    use crate::{
+        save::{
+            load_game_history, load_multi_history, load_multi_random_history, load_random_history,
+            merge_into_history, merge_into_multi_history, test::saved_game, MultiSaveState,
+            SaveState,
+        },
+        store::MemoryStore,
+        GameMode,
+    };
+
+    use crate::versioned;
+
+    use super::{export_csv, export_json, import_json, Export, EXPORT_SCHEMA};
+
+    #[test]
+    fn test_export_and_import() {
+        let lost = ["crane", "slate", "moist", "found", "tiger", "bloke"];
+        let from = MemoryStore::default();
+        for game in [
+            saved_game(GameMode::Daily(400), "pauls", &["crane", "pauls"]),
+            saved_game(GameMode::Daily(401), "pauls", &lost),
+            saved_game(GameMode::Seeded(0x1f), "pauls", &["pauls"]),
+            // never finished
+            saved_game(GameMode::Daily(402), "pauls", &["crane"]),
+        ] {
+            merge_into_history(&from, game);
+        }
+        let to = MemoryStore::default();
+        for game in [
+            saved_game(GameMode::Daily(400), "pauls", &["crane", "pauls"]),
+            saved_game(GameMode::Daily(401), "pauls", &["pauls"]),
+            saved_game(GameMode::Seeded(0x2e), "pauls", &["slate", "pauls"]),
+        ] {
+            merge_into_history(&to, game);
+        }
+
+        assert_eq!(
+            export_csv(&from).lines().collect::<Vec<_>>(),
+            [
+                "date,mode,word,guesses,result".to_string(),
+                format!("{},Daily #400,pauls,2,won", puzzle_date(400)),
+                format!("{},Daily #401,pauls,6,lost", puzzle_date(401)),
+                format!("{},Daily #402,pauls,1,lost", puzzle_date(402)),
+                ",Random r#1f,pauls,1,won".to_string(),
+            ]
+        );
+
+        let report = import_json(&to, &export_json(&from).unwrap()).unwrap();
+        assert_eq!(report.added, 1);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.conflicts, ["Daily #401"]);
+        assert_eq!(report.skipped, 1);
+
+        // the imported random game goes after the one already played here
+        let random = load_random_history(&to, 5, Some(6));
+        assert_eq!(random.scores[&1].game_mode, GameMode::Seeded(0x1f));
+        let daily = load_game_history(&to, 5, Some(6));
+        assert_eq!(daily.scores[&401].guesses.len(), 1);
+        assert!(!daily.scores.contains_key(&402));
+
+        assert!(import_json(&to, "not json").is_err());
+    }
+
+    #[test]
+    fn test_import_skips_unplayable_games() {
+        let mut games = ["pauls", "PAULS", "pa,ls", "paulsandpaulette", "abc"]
+            .into_iter()
+            .enumerate()
+            .map(|(idx, word)| saved_game(GameMode::Daily(400 + idx as u32), word, &[word]))
+            .collect::<Vec<_>>();
+        games.push(SaveState {
+            max_guesses: Some(1000),
+            ..saved_game(GameMode::Daily(410), "pauls", &["pauls"])
+        });
+        let raw = versioned::encode(
+            &Export {
+                games,
+                multi_games: Vec::new(),
+            },
+            &EXPORT_SCHEMA,
+        )
+        .unwrap();
+        let to = MemoryStore::default();
+        let report = import_json(&to, &raw).unwrap();
+        assert_eq!(report.added, 1);
+        assert_eq!(report.skipped, 5);
+        assert_eq!(load_game_history(&to, 5, Some(6)).scores.len(), 1);
+    }
+
+    #[test]
+    fn test_export_multi_games() {
+        let multi = |game_mode: GameMode, guesses: &[&str]| MultiSaveState {
+            words: vec!["crane".into(), "abbey".into()],
+            guesses: guesses.iter().map(|g| g.to_string()).collect(),
+            game_mode,
+            finished_at: None,
+        };
+        let from = MemoryStore::default();
+        merge_into_multi_history(&from, multi(GameMode::Daily(400), &["crane", "abbey"]));
+        merge_into_multi_history(&from, multi(GameMode::Seeded(0x1f), &["abbey", "crane"]));
+
+        assert_eq!(
+            export_csv(&from).lines().skip(1).collect::<Vec<_>>(),
+            [
+                format!("{},Dordle #400,crane abbey,2,won", puzzle_date(400)),
+                ",Random Dordle r#1f,crane abbey,2,won".to_string(),
+            ]
+        );
+
+        let to = MemoryStore::default();
+        let report = import_json(&to, &export_json(&from).unwrap()).unwrap();
+        assert_eq!(report.added, 2);
+        assert_eq!(load_multi_history(&to, 2).scores.len(), 1);
+        assert_eq!(
+            load_multi_random_history(&to, 2).scores[&0].game_mode,
+            GameMode::Seeded(0x1f)
+        );
+        // exports from before multi-board games were included still import
+        let old = r#"{"version":1,"data":{"games":[]}}"#;
+        assert_eq!(import_json(&to, old).unwrap(), Default::default());
+    }
+}
@@ -0,0 +1,204 @@
+//! Everything we keep in storage is wrapped in an envelope recording the version of
+//! its shape, so old data can be brought up to date instead of thrown away.
+//!
+//! Each kind of record has a [`Schema`] with its current version and a chain of
+//! migrations. Data from before envelopes existed is assumed to be at whatever
+//! version the caller says it was written as. Anything that can't be read even
+//! after migrating is moved aside to [`QUARANTINE_KEY`] rather than deleted, so a
+//! bug in a migration never costs anyone their history.
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use web_sys::console;
+
+use crate::store::SaveStore;
+
+pub const QUARANTINE_KEY: &str = "paudle_quarantine_v1";
+
+/// Brings data written at one version up to the next.
+pub type Migration = fn(Value) -> Result<Value, String>;
+
+pub struct Schema {
+    /// The version written now. Bump it and add a migration whenever the stored
+    /// shape changes in a way serde defaults can't cover.
+    pub version: u32,
+    /// `migrations[n]` turns version `n + 1` data into version `n + 2`.
+    pub migrations: &'static [Migration],
+}
+
+#[derive(Serialize, Deserialize)]
+struct Envelope<T> {
+    version: u32,
+    data: T,
+}
+
+/// Data that couldn't be read, kept as it was stored in case it can be recovered.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuarantinedRecord {
+    pub key: String,
+    pub raw: String,
+    pub error: String,
+}
+
+/// Reads a stored record, migrating it to the current version. Raw data without an
+/// envelope is treated as version `unversioned`.
+pub fn decode<T: DeserializeOwned>(
+    raw: &str,
+    schema: &Schema,
+    unversioned: u32,
+) -> Result<T, String> {
+    let value: Value = serde_json::from_str(raw).map_err(|e| e.to_string())?;
+    let (mut version, mut data) = match value {
+        Value::Object(mut map)
+            if map.len() == 2 && map.contains_key("version") && map.contains_key("data") =>
+        {
+            let version = map["version"]
+                .as_u64()
+                .and_then(|v| u32::try_from(v).ok())
+                .ok_or_else(|| "Envelope has no valid version".to_string())?;
+            (version, map.remove("data").unwrap_or(Value::Null))
+        }
+        value => (unversioned, value),
+    };
+    if version == 0 || version > schema.version {
+        return Err(format!(
+            "Can't read version {} data, only up to version {}",
+            version, schema.version
+        ));
+    }
+    while version < schema.version {
+        let migrate = schema
+            .migrations
+            .get(version as usize - 1)
+            .ok_or_else(|| format!("No migration from version {}", version))?;
+        data = migrate(data).map_err(|e| format!("Migrating from version {}: {}", version, e))?;
+        version += 1;
+    }
+    serde_json::from_value(data).map_err(|e| e.to_string())
+}
+
+pub fn encode<T: Serialize>(value: &T, schema: &Schema) -> Result<String, String> {
+    serde_json::to_string(&Envelope {
+        version: schema.version,
+        data: value,
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// Loads the record at `key`, or `None` if there isn't one or it had to be
+/// quarantined.
+pub fn load<T: DeserializeOwned>(
+    store: &dyn SaveStore,
+    key: &str,
+    schema: &Schema,
+    unversioned: u32,
+) -> Option<T> {
+    let raw = store.get(key)?;
+    match decode(&raw, schema, unversioned) {
+        Ok(value) => Some(value),
+        Err(error) => {
+            quarantine(store, key, raw, error);
+            None
+        }
+    }
+}
+
+pub fn store<T: Serialize>(store: &dyn SaveStore, key: &str, value: &T, schema: &Schema) {
+    let stored = encode(value, schema).and_then(|raw| store.set(key, &raw));
+    if let Err(e) = stored {
+        console::log_1(&format!("Couldn't save {}: {}", key, e).into());
+    }
+}
+
+/// Everything that has been quarantined so far, oldest first.
+pub fn quarantined(store: &dyn SaveStore) -> Vec<QuarantinedRecord> {
+    store
+        .get(QUARANTINE_KEY)
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Moves `raw` from `key` into the quarantine list. If that can't be saved the
+/// original is left where it was.
+fn quarantine(store: &dyn SaveStore, key: &str, raw: String, error: String) {
+    let mut records = quarantined(store);
+    records.push(QuarantinedRecord {
+        key: key.to_string(),
+        raw,
+        error,
+    });
+    let saved = serde_json::to_string(&records)
+        .map_err(|e| e.to_string())
+        .and_then(|raw| store.set(QUARANTINE_KEY, &raw));
+    match saved {
+        Ok(()) => store.remove(key),
+        Err(e) => console::log_1(&format!("Couldn't quarantine {}: {}", key, e).into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::{json, Value};
+
+    use crate::store::{MemoryStore, SaveStore};
+
+    use super::{decode, encode, load, quarantined, store, Migration, Schema};
+
+    fn add_count(mut data: Value) -> Result<Value, String> {
+        data["count"] = json!(0);
+        Ok(data)
+    }
+
+    fn double_count(mut data: Value) -> Result<Value, String> {
+        let count = data["count"].as_u64().ok_or("missing count")?;
+        data["count"] = json!(count * 2);
+        Ok(data)
+    }
+
+    const MIGRATIONS: &[Migration] = &[add_count, double_count];
+    const SCHEMA: Schema = Schema {
+        version: 3,
+        migrations: MIGRATIONS,
+    };
+
+    #[test]
+    fn test_migration_chain() {
+        // raw data from before envelopes runs through every step
+        let value: Value = decode(r#"{"name":"a"}"#, &SCHEMA, 1).unwrap();
+        assert_eq!(value, json!({"name": "a", "count": 0}));
+        // enveloped data only runs the steps after its version
+        let value: Value =
+            decode(r#"{"version":2,"data":{"name":"a","count":4}}"#, &SCHEMA, 1).unwrap();
+        assert_eq!(value, json!({"name": "a", "count": 8}));
+        // and round trips at the current version
+        let raw = encode(&value, &SCHEMA).unwrap();
+        assert_eq!(decode::<Value>(&raw, &SCHEMA, 1).unwrap(), value);
+    }
+
+    #[test]
+    fn test_unreadable() {
+        assert!(decode::<Value>("not json", &SCHEMA, 1).is_err());
+        assert!(decode::<Value>(r#"{"version":4,"data":{}}"#, &SCHEMA, 1).is_err());
+        assert!(decode::<Value>(r#"{"version":2,"data":{}}"#, &SCHEMA, 1).is_err());
+    }
+
+    #[test]
+    fn test_quarantine() {
+        let saves = MemoryStore::default();
+        store(&saves, "good", &json!({"count": 1}), &SCHEMA);
+        saves.set("bad", r#"{"version":2,"data":{}}"#).unwrap();
+
+        assert_eq!(
+            load::<Value>(&saves, "good", &SCHEMA, 1),
+            Some(json!({"count": 1}))
+        );
+        assert_eq!(load::<Value>(&saves, "bad", &SCHEMA, 1), None);
+        assert_eq!(load::<Value>(&saves, "missing", &SCHEMA, 1), None);
+
+        // the unreadable record is moved aside as it was, not thrown away
+        assert_eq!(saves.get("bad"), None);
+        let records = quarantined(&saves);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].key, "bad");
+        assert_eq!(records[0].raw, r#"{"version":2,"data":{}}"#);
+    }
+}
@@ -0,0 +1,366 @@
+//! Head-to-head games against other players through `paudle-server`. The server
+//! picks the word and scores every guess; this only sends guesses and shows how
+//! everyone is getting on.
+use gloo_events::EventListener;
+use patternfly_yew::{Backdrop, BackdropDispatcher, Bullseye, Modal, ModalVariant};
+use paudle_core::{
+    is_valid_guess,
+    versus::{ClientMessage, PlayerView, ServerMessage, Standing},
+    Evaluation,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, MessageEvent, WebSocket};
+use yew::prelude::*;
+
+use crate::{
+    board::Board,
+    handle_keypress,
+    keyboard::{Keyboard, KeyboardStatus},
+    listen_for_keys,
+    settings::load_settings,
+    toast_error, PaudleMsg,
+};
+
+/// The round being played, once the server has started one.
+struct VersusRound {
+    you: usize,
+    word_length: usize,
+    max_guesses: usize,
+    current_guess: String,
+    guesses: Vec<Evaluation>,
+    /// A guess has been sent and its score hasn't come back yet.
+    waiting: bool,
+    finished: bool,
+}
+
+impl VersusRound {
+    fn is_over(&self) -> bool {
+        self.finished || self.guesses.len() >= self.max_guesses
+    }
+}
+
+pub struct VersusPaudle {
+    server: NodeRef,
+    room: NodeRef,
+    name: NodeRef,
+    socket: Option<WebSocket>,
+    socket_listeners: Vec<EventListener>,
+    round: Option<VersusRound>,
+    players: Vec<PlayerView>,
+    keyboard_status: KeyboardStatus,
+    keydown_listener: Option<EventListener>,
+}
+
+/// The sync server is the obvious place to play, if there is one.
+fn default_server() -> String {
+    load_settings()
+        .sync
+        .map(|sync| sync.url.replacen("http", "ws", 1))
+        .unwrap_or_default()
+}
+
+fn input_value(input: &NodeRef) -> String {
+    input
+        .cast::<HtmlInputElement>()
+        .map(|input| input.value().trim().to_string())
+        .unwrap_or_default()
+}
+
+impl VersusPaudle {
+    fn connect(&mut self, ctx: &Context<Self>) -> Result<(), String> {
+        let (server, room, name) = (
+            input_value(&self.server),
+            input_value(&self.room),
+            input_value(&self.name),
+        );
+        if !(server.starts_with("ws://") || server.starts_with("wss://")) {
+            return Err("The server needs a ws:// or wss:// address".into());
+        }
+        if room.is_empty() || name.is_empty() {
+            return Err("Choose a room and a name".into());
+        }
+        let url = format!(
+            "{}/rooms/{}/ws",
+            server.trim_end_matches('/'),
+            js_sys::encode_uri_component(&room)
+        );
+        let socket = WebSocket::new(&url).map_err(|e| format!("{:?}", e))?;
+        let join =
+            serde_json::to_string(&ClientMessage::Join { name }).map_err(|e| e.to_string())?;
+        let on_open = {
+            let socket = socket.clone();
+            EventListener::once(&socket.clone(), "open", move |_| {
+                let _ = socket.send_with_str(&join);
+            })
+        };
+        let on_message = {
+            let received = ctx.link().batch_callback(|message: String| {
+                serde_json::from_str(&message)
+                    .ok()
+                    .map(PaudleMsg::VersusMessage)
+            });
+            EventListener::new(&socket, "message", move |e| {
+                if let Some(message) = e
+                    .dyn_ref::<MessageEvent>()
+                    .and_then(|e| e.data().as_string())
+                {
+                    received.emit(message);
+                }
+            })
+        };
+        let on_close = {
+            let closed = ctx.link().callback(|_| PaudleMsg::VersusClosed);
+            EventListener::once(&socket, "close", move |_| closed.emit(()))
+        };
+        self.socket = Some(socket);
+        self.socket_listeners = vec![on_open, on_message, on_close];
+        Ok(())
+    }
+
+    fn disconnect(&mut self) {
+        self.socket_listeners.clear();
+        if let Some(socket) = self.socket.take() {
+            let _ = socket.close();
+        }
+        self.round = None;
+        self.players.clear();
+    }
+
+    fn send(&self, message: &ClientMessage) {
+        if let (Some(socket), Ok(message)) = (&self.socket, serde_json::to_string(message)) {
+            let _ = socket.send_with_str(&message);
+        }
+    }
+
+    fn receive(&mut self, ctx: &Context<Self>, message: ServerMessage) -> bool {
+        match message {
+            ServerMessage::Round {
+                word_length,
+                max_guesses,
+                you,
+            } => {
+                BackdropDispatcher::default().close();
+                self.keyboard_status = KeyboardStatus::default();
+                self.round = Some(VersusRound {
+                    you,
+                    word_length,
+                    max_guesses,
+                    current_guess: String::new(),
+                    guesses: Vec::new(),
+                    waiting: false,
+                    finished: false,
+                });
+            }
+            ServerMessage::Players(players) => self.players = players,
+            ServerMessage::Scored(evaluation) => {
+                if let Some(round) = &mut self.round {
+                    self.keyboard_status.update_status(&evaluation);
+                    round.guesses.push(evaluation);
+                    round.current_guess.clear();
+                    round.waiting = false;
+                }
+            }
+            ServerMessage::Rejected(reason) => {
+                toast_error(reason);
+                if let Some(round) = &mut self.round {
+                    round.waiting = false;
+                }
+            }
+            ServerMessage::Finished { word, standings } => {
+                if let Some(round) = &mut self.round {
+                    round.finished = true;
+                }
+                show_results(ctx, &word, &standings);
+            }
+        }
+        true
+    }
+
+    fn submit(&mut self) -> bool {
+        let Some(round) = &mut self.round else {
+            return false;
+        };
+        if round.is_over() || round.waiting {
+            return false;
+        }
+        if round.current_guess.chars().count() != round.word_length {
+            toast_error("Not enough letters".into());
+        } else if !is_valid_guess(&round.current_guess) {
+            toast_error("Word not in word list".into());
+        } else {
+            round.waiting = true;
+            let guess = ClientMessage::Guess(round.current_guess.clone());
+            self.send(&guess);
+        }
+        true
+    }
+}
+
+#[allow(clippy::unnecessary_operation)]
+fn show_results(ctx: &Context<VersusPaudle>, word: &str, standings: &[Standing]) {
+    let rematch = ctx.link().callback(|_: MouseEvent| PaudleMsg::Rematch);
+    let rows = standings.iter().enumerate().map(|(idx, standing)| {
+        #[allow(clippy::cast_precision_loss)]
+        let secs = standing.millis as f64 / 1000.;
+        let result = match standing.guesses {
+            Some(1) => format!("1 guess, {:.1}s", secs),
+            Some(guesses) => format!("{} guesses, {:.1}s", guesses, secs),
+            None => "didn't solve it".to_string(),
+        };
+        html! { <div>{format!("{}. {}: {}", idx + 1, standing.name, result)}</div> }
+    });
+    BackdropDispatcher::default().open(Backdrop {
+        content: html! {
+            <Bullseye>
+                <Modal
+                    title={format!(r#"The word was "{}""#, word)}
+                    variant={ModalVariant::Small}
+                    footer={Some(html! {
+                        <span class="play-button" onclick={rematch}>{"Rematch"}</span>
+                    })}
+                >
+                    <div class="versus-standings">{ for rows }</div>
+                </Modal>
+            </Bullseye>
+        },
+    });
+}
+
+impl Component for VersusPaudle {
+    type Message = PaudleMsg;
+
+    type Properties = ();
+
+    fn create(_ctx: &Context<Self>) -> Self {
+        Self {
+            server: NodeRef::default(),
+            room: NodeRef::default(),
+            name: NodeRef::default(),
+            socket: None,
+            socket_listeners: Vec::new(),
+            round: None,
+            players: Vec::new(),
+            keyboard_status: KeyboardStatus::default(),
+            keydown_listener: None,
+        }
+    }
+
+    fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+        match msg {
+            PaudleMsg::JoinVersus => {
+                if let Err(e) = self.connect(ctx) {
+                    toast_error(e);
+                }
+                false
+            }
+            PaudleMsg::VersusMessage(message) => self.receive(ctx, message),
+            PaudleMsg::VersusClosed => {
+                toast_error("Lost the connection to the server".into());
+                self.disconnect();
+                true
+            }
+            PaudleMsg::LeaveVersus => {
+                self.disconnect();
+                true
+            }
+            PaudleMsg::Rematch => {
+                self.send(&ClientMessage::Rematch);
+                false
+            }
+            PaudleMsg::TypeLetter(c) => match &mut self.round {
+                Some(round)
+                    if !round.is_over()
+                        && round.current_guess.chars().count() < round.word_length =>
+                {
+                    round.current_guess.push(c.to_ascii_lowercase());
+                    true
+                }
+                _ => false,
+            },
+            PaudleMsg::Backspace => match &mut self.round {
+                Some(round) if !round.waiting => round.current_guess.pop().is_some(),
+                _ => false,
+            },
+            PaudleMsg::Submit => self.submit(),
+            PaudleMsg::Escape => {
+                BackdropDispatcher::default().close();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    #[allow(clippy::unnecessary_operation)]
+    fn view(&self, ctx: &Context<Self>) -> Html {
+        let Some(round) = &self.round else {
+            let join = ctx.link().callback(|_: MouseEvent| PaudleMsg::JoinVersus);
+            return html! {
+                <div class="options">
+                    <div class="versus-lobby">
+                        <input
+                            type="url"
+                            placeholder="Server, e.g. ws://localhost:8787"
+                            value={default_server()}
+                            ref={self.server.clone()}
+                        />
+                        <input type="text" placeholder="Room" ref={self.room.clone()} />
+                        <input type="text" placeholder="Your name" ref={self.name.clone()} />
+                        <span class="play-button" onclick={join}>
+                            {if self.socket.is_some() { "Joining..." } else { "Join" }}
+                        </span>
+                    </div>
+                </div>
+            };
+        };
+        let cb = ctx.link().callback(|msg: PaudleMsg| msg);
+        let leave = ctx.link().callback(|_: MouseEvent| PaudleMsg::LeaveVersus);
+        let opponents = self.players.iter().filter(|p| p.id != round.you);
+
+        html! {
+            <>
+                <div class="options">
+                    <span class="versus-status">
+                        {format!("{} playing", self.players.len())}
+                    </span>
+                    <span class="play-button versus-leave" onclick={leave}>{"Leave"}</span>
+                </div>
+                <Board
+                    current_guess={round.current_guess.clone()}
+                    guesses={round.guesses.clone()}
+                    row_count={round.max_guesses}
+                    word_length={round.word_length}
+                />
+                <div class="boards versus-boards">
+                    { for opponents.map(|player| html! {
+                        <div class="versus-player">
+                            <div class="versus-name">
+                                {&player.name}
+                                if player.finished {
+                                    {" ✓"}
+                                }
+                            </div>
+                            <Board
+                                current_guess={String::new()}
+                                guesses={player.rows.clone()}
+                                row_count={round.max_guesses}
+                                word_length={round.word_length}
+                            />
+                        </div>
+                    }) }
+                </div>
+                <Keyboard key_press={cb} keys={self.keyboard_status.clone()} />
+            </>
+        }
+    }
+
+    fn rendered(&mut self, ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.keydown_listener =
+                Some(listen_for_keys(ctx.link().batch_callback(handle_keypress)));
+        }
+    }
+
+    fn destroy(&mut self, _ctx: &Context<Self>) {
+        self.disconnect();
+    }
+}